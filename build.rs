@@ -0,0 +1,7 @@
+fn main() {
+    #[cfg(feature = "rsvg")]
+    pkg_config::Config::new()
+        .atleast_version("2.46")
+        .probe("librsvg-2.0")
+        .expect("librsvg >= 2.46 is needed for the rsvg feature");
+}
@@ -4,7 +4,9 @@ use pugl_ui::*;
 use pugl_ui::widget::*;
 
 use crate::led;
+use crate::icon::Icon;
 use crate::utils;
+use crate::enable::Enable;
 
 use crate::style;
 
@@ -14,68 +16,246 @@ pub struct Button {
     stub: WidgetStub,
     min_size: Size,
     text: String,
+    icon: Option<Icon>,
+
+    h_align: utils::Align,
+    v_align: utils::Align,
+    min_width: Option<f64>,
+    width_expandable: bool,
+    height_expandable: bool,
 
     clicked: bool,
     active: bool,
+    mouse_down: bool,
+    enabled: bool,
+
+    momentary: bool,
+    pressed: bool,
+    released: bool,
 
-    toggle_state: Option<bool>,
+    states: Vec<ButtonState>,
+    state: Option<usize>,
     led_hue: f64,
 
-    changed_toggle_state: Option<bool>,
+    changed_state: Option<usize>,
+}
+
+/// Text and LED hue of one state of a multi state button. The LED
+/// is off if `led_hue` is `None`.
+struct ButtonState {
+    text: String,
+    led_hue: Option<f64>,
 }
 
 impl Button {
     pub fn new_toggle_button(text: &str, led_hue: f64) -> Box<Button> {
-        let mut btn = Self::new(text);
-        btn.led_hue = led_hue;
+        Self::new_multi_state_button(&[(text, None), (text, Some(led_hue))])
+    }
+
+    /// A button cycling through `states` on each click.
+    ///
+    /// Each state is given by its text and the hue of its LED, `None`
+    /// meaning the LED is off. The first state is the initial one.
+    ///
+    /// Panics if less than two states are given.
+    pub fn new_multi_state_button(states: &[(&str, Option<f64>)]) -> Box<Button> {
+        if states.len() < 2 {
+            panic!("A multi state button needs at least two states");
+        }
+        let mut btn = Self::new(states[0].0);
+        for (text, _) in &states[1..] {
+            let (w, h) = text_size(text);
+            btn.min_size.w = btn.min_size.w.max(w + 2.*PADDING);
+            btn.min_size.h = btn.min_size.h.max(h + 2.*PADDING);
+        }
         btn.min_size.w += style::LED_DIAMETER * 2.0;
-        btn.toggle_state = Some(false);
+        btn.led_hue = states.iter().find_map(|(_, hue)| *hue).unwrap_or(0.0);
+        btn.states = states.iter()
+            .map(|(text, led_hue)| ButtonState { text: String::from(*text), led_hue: *led_hue })
+            .collect();
+        btn.state = Some(0);
         btn
     }
 
-    pub fn new(text: &str) -> Box<Button> {
-        let sf = cairo::ImageSurface::create (cairo::Format::ARgb32, 8, 8).unwrap();
-        let cr = cairo::Context::new (&sf);
+    /// A button that reports when it goes down and when it comes
+    /// back up by `pressed()` and `released()`
+    pub fn new_momentary_button(text: &str) -> Box<Button> {
+        let mut btn = Self::new(text);
+        btn.momentary = true;
+        btn
+    }
 
-        let lyt = utils::pango_layout(text, &cr);
+    /// A button showing only `icon`
+    pub fn new_icon_button(icon: Icon) -> Box<Button> {
+        Self::new_icon_text_button(icon, "")
+    }
+
+    /// A button showing `icon` left of `text`
+    pub fn new_icon_text_button(icon: Icon, text: &str) -> Box<Button> {
+        let mut btn = Self::new(text);
+        let icon_size = icon.size();
+        btn.min_size = if text.is_empty() {
+            Size { w: icon_size.w + 2.*PADDING, h: icon_size.h + 2.*PADDING }
+        } else {
+            Size {
+                w: btn.min_size.w + icon_size.w + PADDING,
+                h: btn.min_size.h.max(icon_size.h + 2.*PADDING)
+            }
+        };
+        btn.icon = Some(icon);
+        btn
+    }
 
-        let (w, h) = lyt.get_pixel_size();
-        let (w, h): (f64, f64) = (w.into(), h.into());
+    pub fn new(text: &str) -> Box<Button> {
+        let (w, h) = text_size(text);
 
         let min_size: Size = Size { w: w + 2.*PADDING, h: h + 2.*PADDING };
 
         Box::new(Button {
             stub: WidgetStub::default(),
             text: String::from(text),
+            icon: None,
+            h_align: utils::Align::Start,
+            v_align: utils::Align::Center,
+            min_width: None,
+            width_expandable: false,
+            height_expandable: false,
             min_size,
             clicked: false,
             active: false,
-            toggle_state: None,
+            mouse_down: false,
+            enabled: true,
+            momentary: false,
+            pressed: false,
+            released: false,
+            states: Vec::new(),
+            state: None,
             led_hue: 0.0,
-            changed_toggle_state: None
+            changed_state: None
         })
     }
 
+    /// Sets the alignment of the text if the button is larger than its
+    /// minimal size. Default is `Start` horizontally and `Center`
+    /// vertically.
+    pub fn set_align(&mut self, horizontal: utils::Align, vertical: utils::Align) {
+        self.h_align = horizontal;
+        self.v_align = vertical;
+        self.ask_for_repaint();
+    }
+
+    /// Overrides the minimal width of the button. If the text does
+    /// not fit, it is ellipsized.
+    pub fn set_min_width(&mut self, min_width: Option<f64>) {
+        self.min_width = min_width;
+    }
+
+    pub fn set_expandable(&mut self, width: bool, height: bool) {
+        self.width_expandable = width;
+        self.height_expandable = height;
+    }
+
     pub fn clicked(&mut self) -> bool {
         let clicked = self.clicked;
         self.clicked = false;
         clicked
     }
 
+    /// Returns `true` once after a momentary button went down
+    pub fn pressed(&mut self) -> bool {
+        let pressed = self.pressed;
+        self.pressed = false;
+        pressed
+    }
+
+    /// Returns `true` once after a momentary button came back up
+    pub fn released(&mut self) -> bool {
+        let released = self.released;
+        self.released = false;
+        released
+    }
+
+    pub fn is_momentary(&self) -> bool {
+        self.momentary
+    }
+
+    /// The state of a toggle button, `None` for other buttons
     pub fn toggle_state(&self) -> Option<bool> {
-        self.toggle_state
+        self.state.filter(|_| self.states.len() == 2).map(|s| s == 1)
     }
 
+    /// Returns the requested toggle state once after a toggle button
+    /// has been clicked. This takes also the `changed_state()`.
     pub fn changed_toggle_state(&mut self) -> Option<bool> {
-        self.changed_toggle_state.take()
+        if self.states.len() != 2 {
+            return None;
+        }
+        self.changed_state.take().map(|s| s == 1)
     }
 
-    pub fn set_toggle_state(&mut self, new_state: bool) {
-        if self.toggle_state.is_some() {
-            self.toggle_state = Some(new_state);
+    /// The index of the state of a multi state or toggle button
+    pub fn state(&self) -> Option<usize> {
+        self.state
+    }
+
+    pub fn state_count(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Returns the next state once after a multi state button has been
+    /// clicked. Like the toggle state it is up to the application to
+    /// accept the new state by `set_state()`.
+    pub fn changed_state(&mut self) -> Option<usize> {
+        self.changed_state.take()
+    }
+
+    /// Sets the state of a multi state or toggle button. Indices out
+    /// of range are ignored.
+    pub fn set_state(&mut self, state: usize) {
+        if state < self.states.len() {
+            self.state = Some(state);
+            self.text = self.states[state].text.clone();
             self.ask_for_repaint();
         }
     }
+
+    fn set_active(&mut self, active: bool) {
+        if active == self.active {
+            return;
+        }
+        self.active = active;
+        if self.momentary {
+            if active {
+                self.pressed = true;
+            } else {
+                self.released = true;
+            }
+        }
+        self.ask_for_repaint();
+    }
+
+    /// Common to mouse and keyboard activation
+    fn activate(&mut self) {
+        self.clicked = true;
+        let state_count = self.states.len();
+        self.changed_state = self.state.map(|s| (s + 1) % state_count);
+    }
+
+    pub fn set_toggle_state(&mut self, new_state: bool) {
+        if self.states.len() == 2 {
+            self.set_state(new_state as usize);
+        }
+    }
+}
+
+fn text_size(text: &str) -> (f64, f64) {
+    let sf = cairo::ImageSurface::create (cairo::Format::ARgb32, 8, 8).unwrap();
+    let cr = cairo::Context::new (&sf);
+
+    let lyt = utils::pango_layout(text, &cr);
+
+    let (w, h) = lyt.get_pixel_size();
+    (w.into(), h.into())
 }
 
 impl Widget for Button {
@@ -86,7 +266,9 @@ impl Widget for Button {
         let size = self.size();
         let pos = self.pos();
 
-        let (r, g, b) =  if self.is_hovered() {
+        let (r, g, b) = if !self.enabled {
+            utils::hsv_to_rgb(0.0, 0.0, style::BRIGHTNESS_BUTTON_DISABLED)
+        } else if self.is_hovered() {
             utils::hsv_to_rgb(0.0, 0.0, style::BRIGHTNESS_BUTTON_HOVER)
         } else {
             utils::hsv_to_rgb(0.0, 0.0, style::BRIGHTNESS_BUTTON_NORMAL)
@@ -112,29 +294,37 @@ impl Widget for Button {
         cr.fill();
         cr.restore();
 
-        if let Some(ts) = self.toggle_state {
-            let mut led = led::LED::new(self.led_hue);
-            if ts {
-                led.set_on(true);
-            }
+        if let Some(state) = self.state {
+            let hue = self.states[state].led_hue;
+            let mut led = led::LED::new(hue.unwrap_or(self.led_hue));
+            led.set_on(hue.is_some()).set_enabled(self.enabled);
             led.render(cr, Coord { x: pos.x + PADDING + style::LED_DIAMETER/2., y: pos.y + size.h/2. });
         }
 
-        cr.save();
-        cr.translate(pos.x + PADDING, pos.y + PADDING);
-        if self.toggle_state.is_some() {
-            cr.translate(style::LED_DIAMETER * 2., 0.0);
+        let v = if self.enabled { 1.0 } else { style::BRIGHTNESS_TEXT_DISABLED };
+        let mut text_x = pos.x + PADDING;
+        if self.state.is_some() {
+            text_x += style::LED_DIAMETER * 2.;
         }
 
-        cr.set_source_rgb (1.0, 1.0, 1.0);
-
-        let lyt = utils::pango_layout(&self.text, &cr);
+        if let Some(icon) = &self.icon {
+            let icon_size = icon.size();
+            let x = if self.text.is_empty() { pos.x + (size.w - icon_size.w)/2. } else { text_x };
+            cr.set_source_rgb (v, v, v);
+            icon.render(cr, Coord { x, y: pos.y + (size.h - icon_size.h)/2. }, v);
+            text_x += icon_size.w + PADDING;
+        }
 
-        pangocairo::functions::show_layout (cr, &lyt);
+        if !self.text.is_empty() || self.icon.is_none() {
+            cr.set_source_rgb (v, v, v);
 
-        cr.restore();
+            let lyt = utils::pango_layout(&self.text, &cr);
+            let text_pos = Coord { x: text_x, y: pos.y + PADDING };
+            let text_size = Size { w: pos.x + size.w - PADDING - text_x, h: size.h - 2.*PADDING };
+            utils::show_layout_aligned(cr, &lyt, text_pos, text_size, self.h_align, self.v_align);
+        }
 
-        if self.has_focus() {
+        if self.enabled && self.has_focus() {
             cr.set_source_rgb (1., 1., 1.);
             cr.rectangle(pos.x, pos.y, size.w, size.h);
             cr.stroke();
@@ -142,27 +332,36 @@ impl Widget for Button {
     }
 
     fn event (&mut self, ev: Event) -> Option<Event> {
+        if !self.enabled {
+            return event_not_processed!().and_then (|es| es.pass_event (ev));
+        }
         match ev.data {
             EventType::MouseMove(_mm) => {
+                if self.mouse_down {
+                    let inside = self.is_hit_by(ev.pos());
+                    self.set_active(inside);
+                }
                 event_processed!()
             }
             EventType::MouseButtonPress(_btn) => {
-                self.active = true;
-                self.ask_for_repaint();
+                self.mouse_down = true;
+                self.set_active(true);
                 event_processed!()
             },
             EventType::MouseButtonRelease(_btn) => {
-                self.clicked = true;
-                self.active = false;
-                self.changed_toggle_state = self.toggle_state.map(|ts| !ts);
+                if self.mouse_down && self.is_hit_by(ev.pos()) {
+                    self.activate();
+                }
+                self.mouse_down = false;
+                self.set_active(false);
 
                 event_processed!()
             },
             EventType::KeyPress(ke) => {
                 ke.try_char().and_then(|c| {
                     match c {
-                        ' ' => {
-                            self.active = true;
+                        ' ' | '\r' | '\n' => {
+                            self.set_active(true);
                             event_processed!()
                         },
                         _ => event_not_processed!()
@@ -172,9 +371,11 @@ impl Widget for Button {
             EventType::KeyRelease(ke) => {
                 ke.try_char().and_then(|c| {
                     match c {
-                        ' ' => {
-                            self.clicked = true;
-                            self.active = false;
+                        ' ' | '\r' | '\n' => {
+                            if self.active {
+                                self.activate();
+                            }
+                            self.set_active(false);
                             event_processed!()
                         },
                         _ => event_not_processed!()
@@ -184,9 +385,40 @@ impl Widget for Button {
             _ => event_not_processed!()
         }.and_then (|es| es.pass_event (ev))
     }
-    fn min_size(&self) -> Size { self.min_size }
+    fn min_size(&self) -> Size {
+        Size { w: self.min_width.unwrap_or(self.min_size.w), h: self.min_size.h }
+    }
+
+    fn width_expandable(&self) -> bool { self.width_expandable }
+    fn height_expandable(&self) -> bool { self.height_expandable }
+
+    fn pointer_leave(&mut self) {
+        if self.mouse_down {
+            self.mouse_down = false;
+            self.set_active(false);
+        }
+    }
+
+    fn takes_focus(&self) -> bool { self.enabled }
+}
+
+impl Enable for Button {
+    fn set_enabled(&mut self, enabled: bool) {
+        if enabled == self.enabled {
+            return;
+        }
+        self.enabled = enabled;
+        if !enabled {
+            self.set_active(false);
+            self.mouse_down = false;
+            self.set_focus(false);
+        }
+        self.ask_for_repaint();
+    }
 
-    fn takes_focus(&self) -> bool { true }
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
 }
 
 #[cfg(all(test, feature="testing"))]
@@ -222,6 +454,10 @@ mod tests {
             (Coord { x: pos.x + size.w/2., y: pos.y + size.h/2. }, button)
         };
 
+        view.queue_event(Event {
+            data: EventType::MouseButtonPress(MouseButton { num: 1, modifiers: Modifiers::default() }),
+            context: EventContext { pos: click_pos, ..Default::default() }
+        });
         view.queue_event(Event {
             data: EventType::MouseButtonRelease(MouseButton { num: 1, modifiers: Modifiers::default() }),
             context: EventContext { pos: click_pos, ..Default::default() }
@@ -229,8 +465,90 @@ mod tests {
 
         let ui = view.handle();
 
+        ui.update(-1.0);
+        assert!(!ui.widget(button).clicked());
+        ui.update(-1.0);
+        assert!(ui.widget(button).clicked());
+    }
+
+    fn button_view(button: Box<Button>) -> (Box<PuglView<UI<RootWidget>>>, Coord, Coord, WidgetHandle<Button>) {
+        let rw = Box::new(RootWidget::default());
+        let mut view = PuglView::new(std::ptr::null_mut(), |pv| UI::new_scaled(pv, rw, 1.0));
+
+        let ui = view.handle();
+        let button = ui.new_widget(button);
+        ui.pack_to_layout(button, ui.root_layout(), StackDirection::Front);
+        ui.do_layout();
+        ui.show_window();
+
+        let w = ui.widget(button);
+        let pos = w.pos();
+        let size = w.size();
+        let inside = Coord { x: pos.x + size.w/2., y: pos.y + size.h/2. };
+        let outside = Coord { x: pos.x + size.w * 2., y: pos.y + size.h * 2. };
+
+        (view, inside, outside, button)
+    }
+
+    fn mouse_event(data: EventType, pos: Coord) -> Event {
+        Event { data, context: EventContext { pos, ..Default::default() } }
+    }
+
+    fn press() -> EventType {
+        EventType::MouseButtonPress(MouseButton { num: 1, modifiers: Modifiers::default() })
+    }
+
+    fn release() -> EventType {
+        EventType::MouseButtonRelease(MouseButton { num: 1, modifiers: Modifiers::default() })
+    }
+
+    fn motion() -> EventType {
+        EventType::MouseMove(MotionContext::default())
+    }
+
+    #[test]
+    fn test_button_release_without_press_no_click() {
+        let (mut view, inside, _, button) = button_view(Button::new("test button"));
+        view.queue_event(mouse_event(release(), inside));
+
+        let ui = view.handle();
+        ui.update(-1.0);
+        assert!(!ui.widget(button).clicked());
+    }
+
+    #[test]
+    fn test_button_drag_out_cancels_click() {
+        let (mut view, inside, outside, button) = button_view(Button::new_toggle_button("test button", 0.0));
+        view.queue_event(mouse_event(press(), inside));
+        view.queue_event(mouse_event(motion(), outside));
+        view.queue_event(mouse_event(release(), outside));
+
+        let ui = view.handle();
+        ui.update(-1.0);
+        assert!(ui.widget(button).active);
+        ui.update(-1.0);
+        assert!(!ui.widget(button).active);
+        ui.update(-1.0);
         assert!(!ui.widget(button).clicked());
+        assert_eq!(ui.widget(button).changed_toggle_state(), None);
+    }
+
+    #[test]
+    fn test_button_drag_out_and_back_clicks() {
+        let (mut view, inside, outside, button) = button_view(Button::new("test button"));
+        view.queue_event(mouse_event(press(), inside));
+        view.queue_event(mouse_event(motion(), outside));
+        view.queue_event(mouse_event(motion(), inside));
+        view.queue_event(mouse_event(release(), inside));
+
+        let ui = view.handle();
+        ui.update(-1.0);
+        ui.update(-1.0);
+        assert!(!ui.widget(button).active);
         ui.update(-1.0);
+        assert!(ui.widget(button).active);
+        ui.update(-1.0);
+        assert!(!ui.widget(button).active);
         assert!(ui.widget(button).clicked());
     }
 
@@ -239,6 +557,10 @@ mod tests {
         let rw = Box::new(RootWidget::default());
         let mut view = PuglView::new(std::ptr::null_mut(), |pv| UI::new_scaled(pv, rw, 1.0));
 
+        view.queue_event(Event {
+            data: EventType::KeyPress(Key { key: KeyVal::Character(' '), modifiers: Modifiers::default(), code: 65 }),
+            context: EventContext::default()
+        });
         view.queue_event(Event {
             data: EventType::KeyRelease(Key { key: KeyVal::Character(' '), modifiers: Modifiers::default(), code: 65 }),
             context: EventContext::default()
@@ -252,6 +574,7 @@ mod tests {
 
         ui.focus_widget(button);
 
+        ui.update(-1.0);
         assert!(!ui.widget(button).clicked());
         ui.update(-1.0);
         assert!(ui.widget(button).clicked());
@@ -321,4 +644,306 @@ mod tests {
         ui.update(-1.0);
         assert!(!ui.widget(button).active);
     }
+
+    #[test]
+    fn test_disabled_button_ignores_click() {
+        let rw = Box::new(RootWidget::default());
+        let mut view = PuglView::new(std::ptr::null_mut(), |pv| UI::new_scaled(pv, rw, 1.0));
+
+        let (click_pos, button) = {
+            let ui = view.handle();
+            let button = ui.new_widget(Button::new("test button"));
+            ui.pack_to_layout(button, ui.root_layout(), StackDirection::Front);
+            ui.do_layout();
+            ui.show_window();
+
+            let w = ui.widget(button);
+            let pos = w.pos();
+            let size = w.size();
+            (Coord { x: pos.x + size.w/2., y: pos.y + size.h/2. }, button)
+        };
+
+        view.queue_event(Event {
+            data: EventType::MouseButtonPress(MouseButton { num: 1, modifiers: Modifiers::default() }),
+            context: EventContext { pos: click_pos, ..Default::default() }
+        });
+        view.queue_event(Event {
+            data: EventType::MouseButtonRelease(MouseButton { num: 1, modifiers: Modifiers::default() }),
+            context: EventContext { pos: click_pos, ..Default::default() }
+        });
+
+        let ui = view.handle();
+        ui.widget(button).set_enabled(false);
+        assert!(!ui.widget(button).is_enabled());
+        assert!(!ui.widget(button).takes_focus());
+
+        ui.update(-1.0);
+        assert!(!ui.widget(button).active);
+        ui.update(-1.0);
+        assert!(!ui.widget(button).clicked());
+    }
+
+    fn key_event(data: fn(Key) -> EventType, c: char) -> Event {
+        Event {
+            data: data(Key { key: KeyVal::Character(c), modifiers: Modifiers::default(), code: 0 }),
+            context: EventContext::default()
+        }
+    }
+
+    #[test]
+    fn test_toggle_button_space_key_toggles() {
+        let (mut view, _, _, button) = button_view(Button::new_toggle_button("test button", 0.0));
+        view.queue_event(key_event(EventType::KeyPress, ' '));
+        view.queue_event(key_event(EventType::KeyRelease, ' '));
+
+        let ui = view.handle();
+        ui.focus_widget(button);
+
+        ui.update(-1.0);
+        assert!(ui.widget(button).active);
+        assert_eq!(ui.widget(button).changed_toggle_state(), None);
+        ui.update(-1.0);
+        assert!(!ui.widget(button).active);
+        assert!(ui.widget(button).clicked());
+        assert_eq!(ui.widget(button).changed_toggle_state(), Some(true));
+    }
+
+    #[test]
+    fn test_button_return_key_click() {
+        let (mut view, _, _, button) = button_view(Button::new("test button"));
+        view.queue_event(key_event(EventType::KeyPress, '\r'));
+        view.queue_event(key_event(EventType::KeyRelease, '\r'));
+
+        let ui = view.handle();
+        ui.focus_widget(button);
+
+        ui.update(-1.0);
+        assert!(ui.widget(button).active);
+        assert!(!ui.widget(button).clicked());
+        ui.update(-1.0);
+        assert!(!ui.widget(button).active);
+        assert!(ui.widget(button).clicked());
+    }
+
+    #[test]
+    fn test_toggle_button_return_key_toggles() {
+        let (mut view, _, _, button) = button_view(Button::new_toggle_button("test button", 0.0));
+        view.handle().widget(button).set_toggle_state(true);
+        view.queue_event(key_event(EventType::KeyPress, '\r'));
+        view.queue_event(key_event(EventType::KeyRelease, '\r'));
+
+        let ui = view.handle();
+        ui.focus_widget(button);
+
+        ui.update(-1.0);
+        ui.update(-1.0);
+        assert_eq!(ui.widget(button).changed_toggle_state(), Some(false));
+    }
+
+    #[test]
+    fn test_momentary_button_mouse() {
+        let (mut view, inside, outside, button) = button_view(Button::new_momentary_button("test button"));
+        view.queue_event(mouse_event(press(), inside));
+        view.queue_event(mouse_event(motion(), outside));
+        view.queue_event(mouse_event(motion(), inside));
+        view.queue_event(mouse_event(release(), inside));
+
+        let ui = view.handle();
+        assert!(ui.widget(button).is_momentary());
+
+        ui.update(-1.0);
+        assert!(ui.widget(button).pressed());
+        assert!(!ui.widget(button).pressed());
+        assert!(!ui.widget(button).released());
+
+        ui.update(-1.0);
+        assert!(ui.widget(button).released());
+        ui.update(-1.0);
+        assert!(ui.widget(button).pressed());
+
+        ui.update(-1.0);
+        assert!(ui.widget(button).released());
+        assert!(!ui.widget(button).released());
+        assert!(!ui.widget(button).pressed());
+        assert!(ui.widget(button).clicked());
+    }
+
+    #[test]
+    fn test_momentary_button_space_key() {
+        let (mut view, _, _, button) = button_view(Button::new_momentary_button("test button"));
+        view.queue_event(key_event(EventType::KeyPress, ' '));
+        view.queue_event(key_event(EventType::KeyPress, ' '));
+        view.queue_event(key_event(EventType::KeyRelease, ' '));
+
+        let ui = view.handle();
+        ui.focus_widget(button);
+
+        ui.update(-1.0);
+        assert!(ui.widget(button).pressed());
+        ui.update(-1.0);
+        assert!(!ui.widget(button).pressed());
+        assert!(!ui.widget(button).released());
+        ui.update(-1.0);
+        assert!(ui.widget(button).released());
+    }
+
+    #[test]
+    fn test_plain_button_no_press_notification() {
+        let (mut view, inside, _, button) = button_view(Button::new("test button"));
+        view.queue_event(mouse_event(press(), inside));
+        view.queue_event(mouse_event(release(), inside));
+
+        let ui = view.handle();
+        ui.update(-1.0);
+        ui.update(-1.0);
+        assert!(!ui.widget(button).pressed());
+        assert!(!ui.widget(button).released());
+        assert!(ui.widget(button).clicked());
+    }
+
+    fn test_icon(w: f64, h: f64) -> Icon {
+        Icon::from_drawing(Size { w, h }, Box::new(|cr: &cairo::Context, size: Size| {
+            cr.rectangle(0.0, 0.0, size.w, size.h);
+            cr.fill();
+        }))
+    }
+
+    #[test]
+    fn test_icon_button_min_size() {
+        let button = Button::new_icon_button(test_icon(24.0, 16.0));
+        assert_eq!(button.min_size(), Size { w: 24.0 + 2.*PADDING, h: 16.0 + 2.*PADDING });
+    }
+
+    #[test]
+    fn test_icon_text_button_min_size() {
+        let text_size = Button::new("load").min_size();
+
+        let button = Button::new_icon_text_button(test_icon(12.0, 4.0), "load");
+        assert_eq!(button.min_size(), Size { w: text_size.w + 12.0 + PADDING, h: text_size.h });
+
+        let button = Button::new_icon_text_button(test_icon(12.0, 64.0), "load");
+        assert_eq!(button.min_size(), Size { w: text_size.w + 12.0 + PADDING, h: 64.0 + 2.*PADDING });
+    }
+
+    #[test]
+    fn test_icon_button_click() {
+        let (mut view, inside, _, button) = button_view(Button::new_icon_button(test_icon(16.0, 16.0)));
+        view.queue_event(mouse_event(press(), inside));
+        view.queue_event(mouse_event(release(), inside));
+
+        let ui = view.handle();
+        ui.update(-1.0);
+        ui.update(-1.0);
+        assert!(ui.widget(button).clicked());
+    }
+
+    #[test]
+    fn test_multi_state_button_cycles() {
+        let states = [("sine", Some(0.0)), ("square", Some(0.3)), ("saw", None)];
+        let (mut view, inside, _, button) = button_view(Button::new_multi_state_button(&states));
+
+        let ui = view.handle();
+        assert_eq!(ui.widget(button).state(), Some(0));
+        assert_eq!(ui.widget(button).state_count(), 3);
+        assert_eq!(ui.widget(button).toggle_state(), None);
+
+        for expected in &[1, 2, 0] {
+            view.queue_event(mouse_event(press(), inside));
+            view.queue_event(mouse_event(release(), inside));
+            let ui = view.handle();
+            ui.update(-1.0);
+            ui.update(-1.0);
+            let w = ui.widget(button);
+            assert_eq!(w.changed_toggle_state(), None);
+            assert_eq!(w.changed_state(), Some(*expected));
+            assert_eq!(w.changed_state(), None);
+            w.set_state(*expected);
+            assert_eq!(w.state(), Some(*expected));
+            assert_eq!(w.text, states[*expected].0);
+        }
+    }
+
+    #[test]
+    fn test_multi_state_button_min_size() {
+        let short = Button::new("a").min_size();
+        let long = Button::new("a much longer text").min_size();
+        let button = Button::new_multi_state_button(&[("a", None), ("a much longer text", Some(0.5))]);
+        assert_eq!(button.min_size().w, long.w + style::LED_DIAMETER * 2.0);
+        assert!(button.min_size().w > short.w + style::LED_DIAMETER * 2.0);
+    }
+
+    #[test]
+    fn test_multi_state_button_set_state_out_of_range() {
+        let mut button = Button::new_multi_state_button(&[("a", None), ("b", Some(0.5))]);
+        button.set_state(2);
+        assert_eq!(button.state(), Some(0));
+        assert_eq!(button.toggle_state(), Some(false));
+        button.set_toggle_state(true);
+        assert_eq!(button.state(), Some(1));
+        assert_eq!(button.text, "b");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_multi_state_button_one_state() {
+        Button::new_multi_state_button(&[("a", None)]);
+    }
+
+    #[test]
+    fn test_button_min_width_and_expandable() {
+        let mut button = Button::new("test button");
+        let natural = button.min_size();
+        assert!(!button.width_expandable());
+        assert!(!button.height_expandable());
+
+        button.set_min_width(Some(20.0));
+        assert_eq!(button.min_size(), Size { w: 20.0, h: natural.h });
+        button.set_min_width(None);
+        assert_eq!(button.min_size(), natural);
+
+        button.set_expandable(true, false);
+        assert!(button.width_expandable());
+        assert!(!button.height_expandable());
+    }
+
+    #[test]
+    fn test_button_key_release_without_press_ignored() {
+        let (mut view, _, _, button) = button_view(Button::new("test button"));
+        view.queue_event(key_event(EventType::KeyRelease, ' '));
+
+        let ui = view.handle();
+        ui.focus_widget(button);
+
+        ui.update(-1.0);
+        assert!(!ui.widget(button).clicked());
+    }
+
+    #[test]
+    fn test_disabled_button_focus_moves_on() {
+        let rw = Box::new(RootWidget::default());
+        let mut view = PuglView::new(std::ptr::null_mut(), |pv| UI::new_scaled(pv, rw, 1.0));
+
+        let ui = view.handle();
+        let first = ui.new_widget(Button::new("first"));
+        let second = ui.new_widget(Button::new("second"));
+        ui.pack_to_layout(first, ui.root_layout(), StackDirection::Back);
+        ui.pack_to_layout(second, ui.root_layout(), StackDirection::Back);
+        ui.do_layout();
+        ui.show_window();
+
+        ui.focus_widget(first);
+        ui.widget(first).set_enabled(false);
+        assert!(!ui.widget(first).has_focus());
+
+        ui.focus_next_widget();
+        assert!(ui.widget(second).has_focus());
+
+        view.queue_event(key_event(EventType::KeyPress, ' '));
+        view.queue_event(key_event(EventType::KeyRelease, ' '));
+        let ui = view.handle();
+        ui.update(-1.0);
+        ui.update(-1.0);
+        assert!(!ui.widget(first).clicked());
+        assert!(ui.widget(second).clicked());
+    }
 }
@@ -5,47 +5,243 @@ use pugl_sys::*;
 
 use crate::utils;
 use crate::style;
+use crate::enable::Enable;
+use crate::tween::{self, Tween, Easing};
 
-pub trait Scale {
-    fn step(min: f64, max: f64, value: f64, total_steps: usize, step: f64) -> f64;
-    fn linear_fraction(min: f64, max: f64, value: f64) -> f64;
-    fn panic_if_invalid_range(_min: f64, _max: f64) {}
+pub trait Scale: Send + Sync {
+    fn step(&self, min: f64, max: f64, value: f64, total_steps: usize, step: f64) -> f64;
+    fn linear_fraction(&self, min: f64, max: f64, value: f64) -> f64;
+    fn value_from_fraction(&self, min: f64, max: f64, fraction: f64) -> f64;
+    fn panic_if_invalid_range(&self, _min: f64, _max: f64) {}
+    fn ticks(&self, min: f64, max: f64) -> Vec<Tick> {
+        linear_ticks(min, max)
+    }
+}
+
+/// A scale chosen at runtime, e.g. from the port properties of an LV2 plugin
+pub type DynScale = Box<dyn Scale>;
+
+impl Scale for DynScale {
+    fn step(&self, min: f64, max: f64, value: f64, total_steps: usize, step: f64) -> f64 {
+        (**self).step(min, max, value, total_steps, step)
+    }
+    fn linear_fraction(&self, min: f64, max: f64, value: f64) -> f64 {
+        (**self).linear_fraction(min, max, value)
+    }
+    fn value_from_fraction(&self, min: f64, max: f64, fraction: f64) -> f64 {
+        (**self).value_from_fraction(min, max, fraction)
+    }
+    fn panic_if_invalid_range(&self, min: f64, max: f64) {
+        (**self).panic_if_invalid_range(min, max)
+    }
+    fn ticks(&self, min: f64, max: f64) -> Vec<Tick> {
+        (**self).ticks(min, max)
+    }
+}
+
+/// A tick mark on a dial's plate at `value`
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Tick {
+    pub value: f64,
+    pub major: bool
+}
+
+/// Returns ticks at "nice" values (multiples of 1, 2 or 5 times a
+/// power of ten) between `min` and `max`.
+pub fn linear_ticks(min: f64, max: f64) -> Vec<Tick> {
+    if !(min.is_finite() && max.is_finite()) || min >= max {
+        return Vec::new();
+    }
+    let raw_step = (max - min) / 5.;
+    let magnitude = 10.0f64.powf(raw_step.log10().floor());
+    let nice = match raw_step / magnitude {
+        n if n <= 1. => 1.,
+        n if n <= 2. => 2.,
+        n if n <= 5. => 5.,
+        _ => 10.
+    };
+    let subdivisions = if nice == 2. { 4 } else { 5 };
+    let minor_step = nice * magnitude / subdivisions as f64;
+
+    let eps = minor_step * 1e-9;
+    let first = ((min - eps) / minor_step).ceil() as i64;
+    let last = ((max + eps) / minor_step).floor() as i64;
+    (first..=last).map(|k| Tick {
+        value: if k == 0 { 0.0 } else { k as f64 * minor_step },
+        major: k.rem_euclid(subdivisions) == 0
+    }).collect()
+}
+
+fn log_ticks(min: f64, max: f64) -> Vec<Tick> {
+    let first_decade = min.log10().floor() as i32;
+    let last_decade = max.log10().ceil() as i32;
+    let with_minors = last_decade - first_decade <= 6;
+    let mut ticks = Vec::new();
+    for decade in first_decade..=last_decade {
+        let base = 10.0f64.powi(decade);
+        for m in 1..10 {
+            if m > 1 && !with_minors {
+                break;
+            }
+            let value = m as f64 * base;
+            if (min * (1. - 1e-9)..=max * (1. + 1e-9)).contains(&value) {
+                ticks.push(Tick { value, major: m == 1 });
+            }
+        }
+    }
+    ticks
 }
 
+#[derive(Default)]
 pub struct LinearScale;
 
 impl Scale for LinearScale {
-    fn step(min: f64, max: f64, value: f64, total_steps: usize, step: f64) -> f64 {
+    fn step(&self, min: f64, max: f64, value: f64, total_steps: usize, step: f64) -> f64 {
         let step_size = (max -  min) / total_steps as f64;
         value + step * step_size
     }
 
-    fn linear_fraction(min: f64, max: f64, value: f64) -> f64 {
+    fn linear_fraction(&self, min: f64, max: f64, value: f64) -> f64 {
          (value - min) / (max - min)
     }
+
+    fn value_from_fraction(&self, min: f64, max: f64, fraction: f64) -> f64 {
+        min + fraction * (max - min)
+    }
 }
 
+#[derive(Default)]
 pub struct LogScale;
 
 impl Scale for LogScale {
-    fn step(min: f64, max: f64, value: f64, total_steps: usize, step: f64) -> f64 {
+    fn step(&self, min: f64, max: f64, value: f64, total_steps: usize, step: f64) -> f64 {
         let step_size = (max/min).log10() / total_steps as f64;
         10.0f64.powf(value.log10() + step * step_size)
     }
-    fn linear_fraction(min: f64, max: f64, value: f64) -> f64 {
+    fn linear_fraction(&self, min: f64, max: f64, value: f64) -> f64 {
          (value/min).log10() / (max/min).log10()
     }
-    fn panic_if_invalid_range(min: f64, max: f64) {
+    fn value_from_fraction(&self, min: f64, max: f64, fraction: f64) -> f64 {
+        min * (max/min).powf(fraction)
+    }
+    fn panic_if_invalid_range(&self, min: f64, max: f64) {
         if min <= 0.0 || max <= 0.0 {
             panic!("LogScale must not have negative or == 0.0 limits.");
         }
     }
+    fn ticks(&self, min: f64, max: f64) -> Vec<Tick> {
+        log_ticks(min, max)
+    }
+}
+
+/// A scale where the linear fraction is `((value - min)/(max - min))^(1/skew)`
+///
+/// A skew of 1.0 is linear, skews greater than 1.0 give more
+/// resolution to the lower end of the range, skews between 0.0 and
+/// 1.0 to the upper end.
+pub struct SkewedScale {
+    skew: f64,
+}
+
+impl SkewedScale {
+    /// Panics if `skew` is not a finite number > 0.0
+    pub fn new(skew: f64) -> SkewedScale {
+        if !(skew > 0.0 && skew.is_finite()) {
+            panic!("SkewedScale must have a skew factor > 0.0.");
+        }
+        SkewedScale { skew }
+    }
+
+    pub fn skew(&self) -> f64 {
+        self.skew
+    }
+}
+
+impl Scale for SkewedScale {
+    fn step(&self, min: f64, max: f64, value: f64, total_steps: usize, step: f64) -> f64 {
+        let fraction = self.linear_fraction(min, max, value) + step / total_steps as f64;
+        self.value_from_fraction(min, max, fraction)
+    }
+    fn linear_fraction(&self, min: f64, max: f64, value: f64) -> f64 {
+        skewed_fraction(min, max, value, self.skew)
+    }
+    fn value_from_fraction(&self, min: f64, max: f64, fraction: f64) -> f64 {
+        skewed_value(min, max, fraction, self.skew)
+    }
+}
+
+const DB_SCALE_SKEW: f64 = 3.0;
+const DB_SCALE_TICK_RANGE: f64 = 60.0;
+
+/// A scale for gains in dB
+///
+/// The knob travel is proportional to the cube root of the linear
+/// gain. The lower limit may be `f64::NEG_INFINITY`, then the
+/// bottom position of the dial is the -inf dB detent.
+#[derive(Default)]
+pub struct DbScale;
+
+impl Scale for DbScale {
+    fn step(&self, min: f64, max: f64, value: f64, total_steps: usize, step: f64) -> f64 {
+        let fraction = self.linear_fraction(min, max, value) + step / total_steps as f64;
+        self.value_from_fraction(min, max, fraction)
+    }
+    fn linear_fraction(&self, min: f64, max: f64, value: f64) -> f64 {
+        skewed_fraction(db_to_gain(min), db_to_gain(max), db_to_gain(value), DB_SCALE_SKEW)
+    }
+    fn value_from_fraction(&self, min: f64, max: f64, fraction: f64) -> f64 {
+        if fraction <= 0.0 {
+            return min;
+        }
+        gain_to_db(skewed_value(db_to_gain(min), db_to_gain(max), fraction, DB_SCALE_SKEW))
+    }
+    fn panic_if_invalid_range(&self, min: f64, max: f64) {
+        if !max.is_finite() || min >= max {
+            panic!("DbScale must have a finite upper limit greater than the lower limit.");
+        }
+    }
+    fn ticks(&self, min: f64, max: f64) -> Vec<Tick> {
+        if min.is_finite() {
+            return linear_ticks(min, max);
+        }
+        let mut ticks = vec![Tick { value: min, major: true }];
+        ticks.extend(linear_ticks(max - DB_SCALE_TICK_RANGE, max)
+                     .into_iter()
+                     .filter(|t| t.major));
+        ticks
+    }
+}
+
+fn skewed_fraction(min: f64, max: f64, value: f64, skew: f64) -> f64 {
+    ((value - min) / (max - min)).max(0.0).powf(1. / skew)
+}
+
+fn skewed_value(min: f64, max: f64, fraction: f64, skew: f64) -> f64 {
+    min + (max - min) * fraction.max(0.0).powf(skew)
+}
+
+fn db_to_gain(db: f64) -> f64 {
+    10.0f64.powf(db / 20.)
 }
 
+fn gain_to_db(gain: f64) -> f64 {
+    20. * gain.log10()
+}
+
+const DOUBLE_CLICK_TIME: f64 = 0.4;
+pub const GESTURE_TIMEOUT: f64 = 0.5;
+
+const HOVER_FADE_TIME: f64 = 0.15;
+
+const DEFAULT_START_ANGLE: f64 = 120.;
+const DEFAULT_SWEEP_ANGLE: f64 = 300.;
+
 pub struct Dial<S: 'static +  Scale> {
     stub: WidgetStub,
     radius: f64,
 
+    scale: S,
+
     value: f64,
     default_value: Option<f64>,
     min_value: f64,
@@ -55,20 +251,81 @@ pub struct Dial<S: 'static +  Scale> {
     changed_value: Option<f64>,
 
     drag_origin: Option<Coord>,
-    //drag_coeff: f64,
+    drag_fraction: Option<f64>,
+    drag_mode: DragMode,
+    drag_sensitivity: Option<f64>,
+    absolute_positioning: bool,
+
+    value_animation: Option<f64>,
+    value_tween: Option<(Tween, f64)>,
 
     hue: Option<f64>,
+    hover: Tween,
     value_indicator_active: bool,
+    enabled: bool,
+
+    start_angle: f64,
+    sweep_angle: f64,
 
-    plate_drawer: &'static (dyn Fn(&Dial<S>, &cairo::Context) + Sync),
+    arc_origin: Option<f64>,
+    modulated_value: Option<f64>,
 
-    formater: &'static (dyn Fn(f64) -> String + Sync),
+    discrete: bool,
+    endless: bool,
+    sweep_before_endless: Option<f64>,
+    step_names: Vec<String>,
+    step_accu: f64,
+
+    plate_drawer: Box<dyn Fn(&Dial<S>, &cairo::Context) + Send + Sync>,
+    plate_margin: f64,
+
+    formater: Box<dyn Fn(f64) -> String + Send + Sync>,
+    parser: Box<dyn Fn(&str) -> Option<f64> + Send + Sync>,
+
+    text_entry: Option<String>,
+    last_click_time: f64,
+    wants_focus: bool,
+
+    gesture: Option<Gesture>,
+    gesture_started: bool,
+    gesture_ended: bool,
+    burst_remaining: f64,
+
+    reminder_period: Option<f64>,
 }
 
-impl<S: Scale> Dial<S> {
+/// The way the pointer movement is translated into value changes
+/// when the dial is dragged.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DragMode {
+    /// Up and right increase, down and left decrease the value
+    Diagonal,
+    /// Only vertical movement changes the value
+    Vertical,
+    /// Only horizontal movement changes the value
+    Horizontal,
+    /// The value follows the angle of the pointer around the dial
+    Circular
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Gesture {
+    Drag,
+    Burst
+}
+
+impl<S: Scale + Default> Dial<S> {
     pub fn new(min_value: f64, max_value: f64, step_num: usize) -> Box<Dial<S>> {
-        S::panic_if_invalid_range(min_value, max_value);
+        Self::with_scale(S::default(), min_value, max_value, step_num)
+    }
+}
+
+impl<S: Scale> Dial<S> {
+    /// Creates a dial with a scale instance, e.g. a `DynScale` chosen at runtime.
+    pub fn with_scale(scale: S, min_value: f64, max_value: f64, step_num: usize) -> Box<Dial<S>> {
+        scale.panic_if_invalid_range(min_value, max_value);
         Box::new(Dial::<S> {
+            scale,
             min_value, max_value, step_num,
 
             value: min_value,
@@ -76,25 +333,109 @@ impl<S: Scale> Dial<S> {
             changed_value: None,
 
             drag_origin: None,
-            //drag_coeff: step * 0.1,
+            drag_fraction: None,
+            drag_mode: DragMode::Diagonal,
+            drag_sensitivity: None,
+            absolute_positioning: false,
+
+            value_animation: None,
+            value_tween: None,
 
             value_indicator_active: false,
+            enabled: true,
             hue: None,
+            hover: Tween::constant(0.0),
             radius: style::DIAL_DIAMETER / 2.,
 
-            plate_drawer: &|_dial, _cr| {},
+            start_angle: DEFAULT_START_ANGLE,
+            sweep_angle: DEFAULT_SWEEP_ANGLE,
+
+            arc_origin: None,
+            modulated_value: None,
 
-            formater: &|value| format!("{:.1}", value),
+            discrete: false,
+            endless: false,
+            sweep_before_endless: None,
+            step_names: Vec::new(),
+            step_accu: 0.0,
+
+            plate_drawer: Box::new(|_dial: &Dial<S>, _cr: &cairo::Context| {}),
+            plate_margin: 0.0,
+
+            formater: Box::new(|value: f64| format!("{:.1}", value)),
+            parser: Box::new(|text: &str| text.trim().parse().ok()),
+
+            text_entry: None,
+            last_click_time: f64::NEG_INFINITY,
+            wants_focus: false,
+
+            gesture: None,
+            gesture_started: false,
+            gesture_ended: false,
+            burst_remaining: 0.0,
+
+            reminder_period: None,
 
             stub: WidgetStub::default()
         })
     }
 
+    /// Sets the value, e.g. when the host sends a new parameter value.
+    ///
+    /// If a value animation is set, the dial moves smoothly towards
+    /// the new value. The animation is skipped while the user is
+    /// changing the value.
     pub fn set_value(&mut self, v: f64) {
-        self.value = v;
+        match self.value_animation {
+            Some(duration) if self.gesture.is_none() && v.is_finite() && self.value.is_finite() => {
+                let from = self.value_fraction();
+                let to = self.scale.linear_fraction(self.min_value, self.max_value, v);
+                self.value_tween = Some((Tween::new(from, to, duration, Easing::EaseOut), v));
+                self.schedule_reminder();
+            }
+            _ => {
+                self.value_tween = None;
+                self.value = v;
+            }
+        }
         self.ask_for_repaint();
     }
 
+    /// Sets the duration in seconds of the animation `set_value()`
+    /// uses to move towards a new value. `None` (default) disables
+    /// the animation.
+    pub fn set_value_animation(&mut self, duration: Option<f64>) {
+        self.value_animation = duration.filter(|d| *d > 0.0);
+        if self.value_animation.is_none() {
+            self.finish_value_animation();
+        }
+    }
+
+    pub fn value_animation(&self) -> Option<f64> {
+        self.value_animation
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.value_tween.is_some() || self.hover.is_running()
+    }
+
+    fn finish_value_animation(&mut self) {
+        if let Some((_, target)) = self.value_tween.take() {
+            self.value = target;
+            self.ask_for_repaint();
+        }
+    }
+
+    /// Sets the value by the linear fraction of the dial's range,
+    /// e.g. for automation by the host.
+    pub fn set_value_fraction(&mut self, fraction: f64) {
+        self.set_value(self.scale.value_from_fraction(self.min_value, self.max_value, fraction));
+    }
+
+    pub fn value_fraction(&self) -> f64 {
+        self.scale.linear_fraction(self.min_value, self.max_value, self.value)
+    }
+
     pub fn set_default_value(&mut self, v: f64) {
         self.default_value = Some(v);
     }
@@ -103,14 +444,189 @@ impl<S: Scale> Dial<S> {
         self.changed_value.take()
     }
 
+    /// Returns `true` once if the user has started to change the
+    /// value, e.g. to be passed to the host as touch notification.
+    pub fn gesture_started(&mut self) -> bool {
+        let started = self.gesture_started;
+        self.gesture_started = false;
+        started
+    }
+
+    /// Returns `true` once if the user has finished to change the value.
+    ///
+    /// For dragging the gesture ends with the mouse button release,
+    /// for scrolling and keyboard input after `GESTURE_TIMEOUT`
+    /// seconds of inactivity.
+    pub fn gesture_ended(&mut self) -> bool {
+        let ended = self.gesture_ended;
+        self.gesture_ended = false;
+        ended
+    }
+
+    pub fn gesture_ongoing(&self) -> bool {
+        self.gesture.is_some()
+    }
+
+    fn begin_gesture(&mut self, gesture: Gesture) {
+        self.finish_value_animation();
+        if self.gesture.is_none() {
+            self.gesture_started = true;
+        }
+        self.gesture = Some(gesture);
+    }
+
+    fn end_gesture(&mut self) {
+        if self.gesture.take().is_some() {
+            self.gesture_ended = true;
+        }
+    }
+
+    fn touch_burst(&mut self) {
+        if self.gesture == Some(Gesture::Drag) {
+            return;
+        }
+        self.begin_gesture(Gesture::Burst);
+        self.burst_remaining = GESTURE_TIMEOUT;
+        self.schedule_reminder();
+    }
+
+    /// Requests the reminder for all pending timed tasks.
+    ///
+    /// There is only one timer per widget. While animating it ticks
+    /// with `tween::FRAME_PERIOD` and the scroll burst timeout is
+    /// counted down by the frames. Otherwise the timer is (re)started
+    /// with the remaining burst timeout.
+    ///
+    /// Returns `true` if the timer is still needed.
+    fn schedule_reminder(&mut self) -> bool {
+        if self.is_animating() {
+            if self.reminder_period != Some(tween::FRAME_PERIOD) {
+                self.reminder_period = Some(tween::FRAME_PERIOD);
+                self.request_reminder(tween::FRAME_PERIOD);
+            }
+            return true;
+        }
+        if self.gesture == Some(Gesture::Burst) && self.burst_remaining > 0.0 {
+            self.reminder_period = Some(self.burst_remaining);
+            self.request_reminder(self.burst_remaining);
+            return true;
+        }
+        self.reminder_period = None;
+        false
+    }
+
+    pub fn set_drag_mode(&mut self, drag_mode: DragMode) {
+        self.drag_mode = drag_mode;
+    }
+
+    pub fn drag_mode(&self) -> DragMode {
+        self.drag_mode
+    }
+
+    /// Makes a click on the dial's ring jump to the value at the
+    /// clicked angle. Off by default.
+    pub fn set_absolute_positioning(&mut self, absolute: bool) {
+        self.absolute_positioning = absolute;
+    }
+
+    pub fn is_absolute_positioning(&self) -> bool {
+        self.absolute_positioning
+    }
+
+    /// Sets the pointer distance in pixels it takes to drag the dial
+    /// over its full range.
+    ///
+    /// If `None` (the default) it takes 100 pixels per step.
+    /// Holding Ctrl while dragging makes the movement ten times
+    /// coarser, holding Shift ten times finer.
+    pub fn set_drag_sensitivity(&mut self, pixels_per_range: Option<f64>) {
+        self.drag_sensitivity = pixels_per_range.filter(|px| *px > 0.0);
+    }
+
+    fn drag_steps(&self, origin: Coord, pos: Coord, modifiers: Modifiers) -> f64 {
+        let diff = match self.drag_mode {
+            DragMode::Vertical => origin.y - pos.y,
+            DragMode::Horizontal => pos.x - origin.x,
+            _ => (pos.x - origin.x) - (pos.y - origin.y)
+        };
+        let steps_per_pixel = match self.drag_sensitivity {
+            Some(px) => self.step_num as f64 / px,
+            None => 0.01
+        };
+        refine_step_by_modifiers(modifiers) * 10. * diff * steps_per_pixel
+    }
+
+    fn circular_drag_steps(&mut self, pos: Coord, modifiers: Modifiers) -> f64 {
+        let fraction = match self.fraction_at(pos) {
+            Some(fraction) => fraction,
+            None => return 0.0
+        };
+        let delta = self.drag_fraction.map_or(0.0, |last| fraction - last);
+        self.drag_fraction = Some(fraction);
+        let delta = if self.endless {
+            delta - delta.round()
+        } else if delta.abs() > 0.5 {
+            return 0.0;
+        } else {
+            delta
+        };
+        let refine = if modifiers.contains(Modifiers::SHIFT) { 0.1 } else { 1.0 };
+        delta * self.step_num as f64 * refine
+    }
+
     pub fn set_hue(&mut self, hue: Option<f64>) {
         self.hue = hue;
     }
 
-    pub fn set_plate_draw(&mut self, draw_func: &'static (dyn Fn(&Dial<S>, &cairo::Context) + Sync)) {
+    pub fn hue(&self) -> Option<f64> {
+        self.hue
+    }
+
+    /// The value of the dial. While animating towards a value set by
+    /// `set_value()` this is already the new value.
+    pub fn value(&self) -> f64 {
+        self.value_tween.as_ref().map_or(self.value, |(_, target)| *target)
+    }
+
+    pub fn default_value(&self) -> Option<f64> {
+        self.default_value
+    }
+
+    pub fn min_value(&self) -> f64 {
+        self.min_value
+    }
+
+    pub fn max_value(&self) -> f64 {
+        self.max_value
+    }
+
+    /// Sets the function drawing the dial's plate, e.g. tick marks.
+    ///
+    /// The function draws relative to the widget's upper left corner
+    /// and is clipped to the widget.
+    pub fn set_plate_draw(&mut self, draw_func: Box<dyn Fn(&Dial<S>, &cairo::Context) + Send + Sync>) {
         self.plate_drawer = draw_func;
     }
 
+    /// Reserves `margin` pixels around the dial for the plate, e.g.
+    /// for the labels of `draw_scale_tics()` or `draw_step_labels()`.
+    pub fn set_plate_margin(&mut self, margin: f64) {
+        self.plate_margin = margin.max(0.0);
+    }
+
+    pub fn plate_margin(&self) -> f64 {
+        self.plate_margin
+    }
+
+    /// The center of the dial relative to the widget's upper left corner
+    pub fn plate_center(&self) -> Coord {
+        Coord { x: 2.*self.radius + self.plate_margin, y: 2.*self.radius + 6.0 + self.plate_margin }
+    }
+
+    pub fn scale(&self) -> &S {
+        &self.scale
+    }
+
     pub fn radius(&self) -> f64 {
         self.radius
     }
@@ -123,16 +639,284 @@ impl<S: Scale> Dial<S> {
         self.radius = style::DIAL_DIAMETER / 3.0;
     }
 
-    pub fn set_formater(&mut self, format_func: &'static (dyn Fn(f64) -> String + Sync)) {
+    /// Sets the geometry of the dial's value arc in degrees.
+    ///
+    /// `start_angle` is the position of the minimum value measured
+    /// clockwise from the three o'clock position, `sweep_angle` is the
+    /// clockwise angle from the minimum to the maximum value.
+    pub fn set_angle_range(&mut self, start_angle: f64, sweep_angle: f64) {
+        self.start_angle = start_angle;
+        self.sweep_angle = sweep_angle.max(0.0).min(360.0);
+        self.ask_for_repaint();
+    }
+
+    pub fn start_angle(&self) -> f64 {
+        self.start_angle
+    }
+
+    pub fn sweep_angle(&self) -> f64 {
+        self.sweep_angle
+    }
+
+    /// Returns the angle in radians that corresponds to the linear
+    /// `fraction` of the dial's range.
+    pub fn fraction_angle(&self, fraction: f64) -> f64 {
+        (self.start_angle + self.sweep_angle * fraction) * PI/180.
+    }
+
+    /// Returns the linear fraction of the dial's range that is
+    /// pointed at by `pos`, or `None` if `pos` lies outside the
+    /// dial's sweep.
+    pub fn fraction_at(&self, pos: Coord) -> Option<f64> {
+        let center = self.center();
+        let (dx, dy) = (pos.x - center.x, pos.y - center.y);
+        if dx == 0.0 && dy == 0.0 {
+            return None;
+        }
+        let angle = (dy.atan2(dx) * 180./PI - self.start_angle).rem_euclid(360.);
+        if self.sweep_angle > 0.0 && angle <= self.sweep_angle {
+            Some(angle / self.sweep_angle)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the value from which a filled value arc is drawn to the
+    /// current value, e.g. `Some(0.0)` for a bipolar dial.
+    ///
+    /// If `None` only the value indicator is drawn.
+    pub fn set_arc_origin(&mut self, origin: Option<f64>) {
+        self.arc_origin = origin;
+        self.ask_for_repaint();
+    }
+
+    pub fn arc_origin(&self) -> Option<f64> {
+        self.arc_origin
+    }
+
+    /// Sets the effective value of a modulated parameter to be shown
+    /// as a secondary arc beside the value set by the user.
+    ///
+    /// Does not affect the value reported by `changed_value()`.
+    pub fn set_modulated_value(&mut self, value: Option<f64>) {
+        if self.modulated_value != value {
+            self.modulated_value = value;
+            self.ask_for_repaint();
+        }
+    }
+
+    pub fn modulated_value(&self) -> Option<f64> {
+        self.modulated_value
+    }
+
+    fn widget_rgb(&self, hue: Option<f64>) -> utils::RGB {
+        if self.enabled {
+            utils::widget_rgb_hover_fraction(self.hover.value(), hue)
+        } else {
+            utils::widget_rgb_disabled(hue)
+        }
+    }
+
+    fn center(&self) -> Coord {
+        self.pos() + self.plate_center()
+    }
+
+    fn is_on_ring(&self, pos: Coord) -> bool {
+        let center = self.center();
+        let dist = (pos.x - center.x).hypot(pos.y - center.y);
+        dist > self.radius * 0.8 && dist < self.radius * 1.2
+    }
+
+    pub fn set_formater(&mut self, format_func: Box<dyn Fn(f64) -> String + Send + Sync>) {
         self.formater = format_func;
     }
 
+    /// Sets the function to parse the text typed into the dial's
+    /// text entry. Supposed to return `None` if the text is invalid.
+    pub fn set_parser(&mut self, parse_func: Box<dyn Fn(&str) -> Option<f64> + Send + Sync>) {
+        self.parser = parse_func;
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.text_entry.is_some()
+    }
+
+    /// Returns `true` once if the text entry has been opened by a
+    /// double click while the dial did not have the focus.
+    ///
+    /// As widgets can not take the focus by themselves, the
+    /// application should then call `UI::focus_widget()` so that the
+    /// typed keys reach the dial.
+    pub fn wants_focus(&mut self) -> bool {
+        let wants_focus = self.wants_focus;
+        self.wants_focus = false;
+        wants_focus
+    }
+
+    fn start_text_entry(&mut self) {
+        self.wants_focus = !self.has_focus();
+        self.text_entry = Some(self.value_text());
+        self.drag_origin = None;
+        self.ask_for_repaint();
+    }
+
+    fn cancel_text_entry(&mut self) {
+        self.text_entry = None;
+        self.ask_for_repaint();
+    }
+
+    fn commit_text_entry(&mut self) {
+        if let Some(text) = self.text_entry.take() {
+            self.touch_burst();
+            let named = self.step_names.iter()
+                .position(|name| name.eq_ignore_ascii_case(text.trim()))
+                .map(|i| self.scale.value_from_fraction(self.min_value, self.max_value, i as f64 / self.step_num as f64));
+            let value = named.or_else(|| (self.parser)(&text));
+            if let Some(value) = value {
+                self.update_value(value);
+            }
+        }
+        self.ask_for_repaint();
+    }
+
+    fn text_entry_key(&mut self, key: Key) {
+        match key.key {
+            KeyVal::Special(SpecialKey::Escape) => self.cancel_text_entry(),
+            KeyVal::Character('\r') | KeyVal::Character('\n') => self.commit_text_entry(),
+            KeyVal::Special(SpecialKey::Backspace) | KeyVal::Character('\u{8}') => {
+                if let Some(text) = self.text_entry.as_mut() {
+                    text.pop();
+                }
+                self.ask_for_repaint();
+            }
+            KeyVal::Character(c) if !c.is_control() => {
+                if let Some(text) = self.text_entry.as_mut() {
+                    text.push(c);
+                }
+                self.ask_for_repaint();
+            }
+            _ => {}
+        }
+    }
+
+    /// Makes the dial wrap around from the maximum to the minimum
+    /// value and vice versa instead of stopping at the limits.
+    ///
+    /// Enabling it sets the sweep angle to a full circle, disabling
+    /// it restores the previous sweep angle.
+    pub fn set_endless(&mut self, endless: bool) {
+        if endless && !self.endless {
+            self.sweep_before_endless = Some(self.sweep_angle);
+            self.set_angle_range(self.start_angle, 360.);
+        }
+        if !endless {
+            if let Some(sweep) = self.sweep_before_endless.take() {
+                self.set_angle_range(self.start_angle, sweep);
+            }
+        }
+        self.endless = endless;
+    }
+
+    pub fn is_endless(&self) -> bool {
+        self.endless
+    }
+
+    /// Makes the dial snap to the `step_num + 1` discrete positions of its range.
+    pub fn set_discrete(&mut self, discrete: bool) {
+        self.discrete = discrete;
+        self.step_accu = 0.0;
+    }
+
+    pub fn is_discrete(&self) -> bool {
+        self.discrete
+    }
+
+    /// Makes the dial discrete with one position for each of the `names`.
+    ///
+    /// The names are shown instead of the formatted value.
+    pub fn set_step_names(&mut self, names: &[&str]) {
+        if names.len() < 2 {
+            panic!("A Dial needs at least two step names.");
+        }
+        self.step_names = names.iter().map(|n| n.to_string()).collect();
+        self.step_num = names.len() - 1;
+        self.set_discrete(true);
+        self.ask_for_repaint();
+    }
+
+    pub fn step_names(&self) -> &[String] {
+        &self.step_names
+    }
+
+    pub fn step_num(&self) -> usize {
+        self.step_num
+    }
+
+    /// Returns the index of the discrete position closest to `value`.
+    pub fn step_index(&self, value: f64) -> usize {
+        let fraction = self.scale.linear_fraction(self.min_value, self.max_value, value);
+        (fraction * self.step_num as f64).round().max(0.0).min(self.step_num as f64) as usize
+    }
+
+    fn snap(&self, value: f64) -> f64 {
+        let fraction = self.step_index(value) as f64 / self.step_num as f64;
+        self.scale.value_from_fraction(self.min_value, self.max_value, fraction)
+    }
+
+    fn value_text(&self) -> String {
+        if self.discrete && !self.step_names.is_empty() {
+            self.step_names[self.step_index(self.value)].clone()
+        } else {
+            (self.formater)(self.value)
+        }
+    }
+
+    fn step_by_modifiers(&self, modifiers: Modifiers) -> f64 {
+        if self.discrete {
+            1.0
+        } else {
+            refine_step_by_modifiers(modifiers)
+        }
+    }
+
+    fn reset_to_default(&mut self) {
+        if let Some(default) = self.default_value {
+            if (self.value - default).abs() > f64::EPSILON * self.value {
+                self.changed_value = Some(default);
+            }
+        }
+    }
+
+    fn step_value(&mut self, step: f64) {
+        self.finish_value_animation();
+        let step = if self.discrete {
+            self.step_accu += step;
+            let whole = self.step_accu.trunc();
+            self.step_accu -= whole;
+            if whole == 0.0 {
+                return;
+            }
+            whole
+        } else {
+            step
+        };
+        if self.endless {
+            let fraction = self.value_fraction() + step / self.step_num as f64;
+            let fraction = if !(0.0..=1.0).contains(&fraction) { fraction.rem_euclid(1.0) } else { fraction };
+            self.update_value(self.scale.value_from_fraction(self.min_value, self.max_value, fraction));
+        } else {
+            self.update_value(self.scale.step(self.min_value, self.max_value, self.value, self.step_num, step));
+        }
+    }
+
     fn update_value(&mut self, new_value: f64) {
+        self.finish_value_animation();
         let new_value = match new_value {
             v if v > self.max_value => self.max_value,
             v if v < self.min_value => self.min_value,
             _ => new_value
         };
+        let new_value = if self.discrete { self.snap(new_value) } else { new_value };
         if (new_value - self.value).abs() > f64::EPSILON * self.value {
             self.changed_value = Some(new_value)
         }
@@ -145,18 +929,18 @@ impl<S: Scale> Widget for Dial<S> {
 
     fn exposed (&mut self, _exposed: &ExposeArea, cr: &cairo::Context) {
 
-        let pos = self.pos() + Coord { x: 2.*self.radius, y: 2.*self.radius };
+        let pos = self.pos() + Coord { x: 2.*self.radius + self.plate_margin, y: 2.*self.radius + self.plate_margin };
         cr.save();
 
         cr.translate(pos.x, pos.y + 6.0);
 
         cr.set_source(&utils::inactive_gradient(Coord {x: 0.0, y: -self.radius},
                                                 Size { w: 2.*self.radius, h: self.radius },
-                                                utils::widget_rgb(self.is_hovered(), None)));
+                                                self.widget_rgb(None)));
         cr.arc(0., 0., self.radius * 0.8, 0.0, 2.*PI);
         cr.fill();
 
-        let (r, g, b) = utils::widget_rgb(self.is_hovered(), self.hue);
+        let (r, g, b) = self.widget_rgb(self.hue);
 
         cr.set_source(&utils::active_gradient(Coord {x: 0.0, y: -0.4*self.radius},
                                              Size { w: 0.2*self.radius, h: 0.8*self.radius },
@@ -170,65 +954,127 @@ impl<S: Scale> Widget for Dial<S> {
         cr.arc(0., 0., self.radius, 0.0, 2.*PI);
         cr.stroke();
 
-        let angle = 120. + 300. * S::linear_fraction(self.min_value, self.max_value, self.value);
-        cr.set_source_rgb(1., 1., 1.);
+        let fraction = self.scale.linear_fraction(self.min_value, self.max_value, self.value);
+
+        if let Some(origin) = self.arc_origin {
+            let origin = self.scale.linear_fraction(self.min_value, self.max_value, origin);
+            let (from, to) = if origin < fraction { (origin, fraction) } else { (fraction, origin) };
+            let (from, to) = (from.max(0.0), to.min(1.0));
+            if from < to {
+                cr.set_source_rgb(r, g, b);
+                cr.set_line_width(self.radius * 0.2);
+                cr.arc(0., 0., self.radius, self.fraction_angle(from), self.fraction_angle(to));
+                cr.stroke();
+            }
+        }
+
+        let angle = self.fraction_angle(fraction);
+        let v = if self.enabled { 1.0 } else { style::BRIGHTNESS_TEXT_DISABLED };
+        cr.set_source_rgb(v, v, v);
         cr.set_line_width(self.radius * 0.2);
-        cr.arc(0., 0., self.radius, (angle-10.0) * PI/180., (angle+10.0) * PI/180.);
+        cr.arc(0., 0., self.radius, angle - 10.0 * PI/180., angle + 10.0 * PI/180.);
         cr.stroke();
 
-        cr.restore();
-        cr.save();
+        if let Some(modulated) = self.modulated_value {
+            let modulated = self.scale.linear_fraction(self.min_value, self.max_value, modulated).max(0.0).min(1.0);
+            let (from, to) = if modulated < fraction { (modulated, fraction) } else { (fraction, modulated) };
+            let (r, g, b) = self.hue.map_or((1., 1., 1.), |h| utils::hsv_to_rgb(h, 0.5, 1.0));
+            let mod_radius = self.radius * 1.25;
+            cr.set_source_rgb(r, g, b);
+            cr.set_line_width(self.radius * 0.06);
+            cr.arc(0., 0., mod_radius, self.fraction_angle(from.max(0.0)), self.fraction_angle(to.min(1.0)));
+            cr.stroke();
 
-        if self.value_indicator_active {
+            let (sin, cos) = self.fraction_angle(modulated).sin_cos();
+            cr.arc(mod_radius * cos, mod_radius * sin, self.radius * 0.1, 0.0, 2.*PI);
+            cr.fill();
+        }
+
+        cr.restore();
+        cr.save();
+
+        if self.value_indicator_active || self.text_entry.is_some() {
             let ctx = pangocairo::functions::create_context(&cr).expect("cration of pango context failed");
             let lyt = pango::Layout::new(&ctx);
             let font_desc = pango::FontDescription::from_string("Sans 8px");
 
             lyt.set_font_description(Some(&font_desc));
-            lyt.set_text(&(self.formater)(self.value));
+            match &self.text_entry {
+                Some(text) => lyt.set_text(&format!("{}|", text)),
+                None => lyt.set_text(&self.value_text())
+            }
 
             let (ent, _) = lyt.get_extents();
             let (w, h) = ((ent.width/pango::SCALE) as f64, (ent.height/pango::SCALE) as f64);
             let bl = (lyt.get_baseline()/pango::SCALE) as f64;
 
             cr.translate(pos.x-w/2., pos.y-self.size().h/2. + h - 6.0);
-            cr.set_source_rgb(0., 0., 0.);
+            let (bg, fg) = if self.text_entry.is_some() { (1., 0.) } else { (0., 1.) };
+            cr.set_source_rgb(bg, bg, bg);
             cr.rectangle(0., 0., w, h+(bl/2.));
             cr.fill();
-            cr.set_source_rgb(1., 1., 1.);
+            cr.set_source_rgb(fg, fg, fg);
             pangocairo::functions::show_layout(cr, &lyt);
         }
         cr.restore();
 
         cr.save();
-        cr.translate(pos.x - 2.*self.radius, pos.y -2.*self.radius);
+        let (x, y, w, h) = self.rect();
+        cr.rectangle(x, y, w, h);
+        cr.clip();
+        cr.translate(x, y);
         (self.plate_drawer)(self, cr);
 
         cr.restore();
+
+        if self.enabled && self.has_focus() {
+            let (x, y, w, h) = self.rect();
+            cr.set_source_rgb (1., 1., 1.);
+            cr.set_line_width(1.0);
+            cr.rectangle(x, y, w, h);
+            cr.stroke();
+        }
     }
 
     fn event(&mut self, ev: Event) -> Option<Event> {
+        if !self.enabled {
+            return event_not_processed!().and_then (|es| es.pass_event (ev));
+        }
         match ev.data {
             EventType::Scroll (sc) => {
-                let step = refine_step_by_modifiers(sc.modifiers);
-                self.update_value(S::step(
-                    self.min_value, self.max_value, self.value, self.step_num,
-                    step * sc.dy.signum()
-                ));
+                let step = self.step_by_modifiers(sc.modifiers);
+                self.touch_burst();
+                self.step_value(step * sc.dy.signum());
+                event_processed!()
+            }
+            EventType::MouseButtonPress(_) if self.text_entry.is_some() => {
+                self.commit_text_entry();
                 event_processed!()
             }
             EventType::MouseButtonPress(btn) => {
                 match btn.num {
+                    1 if ev.context.time - self.last_click_time < DOUBLE_CLICK_TIME => {
+                        self.last_click_time = f64::NEG_INFINITY;
+                        self.start_text_entry();
+                        event_processed!()
+                    }
                     1 => {
+                        self.last_click_time = ev.context.time;
+                        self.begin_gesture(Gesture::Drag);
+                        let pos = ev.pos();
+                        if self.absolute_positioning && self.is_on_ring(pos) {
+                            if let Some(fraction) = self.fraction_at(pos) {
+                                self.update_value(self.scale.value_from_fraction(self.min_value, self.max_value, fraction));
+                            }
+                        }
                         self.drag_origin = Some(ev.pos_root());
+                        self.drag_fraction = self.fraction_at(pos);
+                        self.step_accu = 0.0;
                         event_processed!()
                     }
                     3 => {
-                        if let Some(default) = self.default_value {
-                            if (self.value - default).abs() > f64::EPSILON * self.value {
-                                self.changed_value = Some(default);
-                            }
-                        }
+                        self.touch_burst();
+                        self.reset_to_default();
                         event_processed!()
                     }
                     _ => event_not_processed!()
@@ -238,6 +1084,9 @@ impl<S: Scale> Widget for Dial<S> {
                 match btn.num {
                     1 => {
                         self.drag_origin = None;
+                        if self.gesture == Some(Gesture::Drag) {
+                            self.end_gesture();
+                        }
                         event_processed!()
                     }
                     _ => event_not_processed!()
@@ -247,56 +1096,1200 @@ impl<S: Scale> Widget for Dial<S> {
                 match self.drag_origin {
                     Some(origin) => {
                         let pos = ev.pos_root();
-                        let diff = (pos.x - origin.x) - (pos.y - origin.y);
-                        let step = refine_step_by_modifiers(mm.modifiers);
-                        self.update_value(S::step(
-                            self.min_value, self.max_value, self.value, self.step_num,
-                            step * diff * 0.1
-                        ));
+                        let steps = match self.drag_mode {
+                            DragMode::Circular => self.circular_drag_steps(ev.pos(), mm.modifiers),
+                            _ => self.drag_steps(origin, pos, mm.modifiers)
+                        };
+                        self.step_value(steps);
                         self.drag_origin = Some(pos);
                         event_processed!()
                     }
                     None => event_not_processed!()
                 }
             }
+            EventType::KeyPress(key) if self.text_entry.is_some() => {
+                self.text_entry_key(key);
+                event_processed!()
+            }
+            EventType::KeyPress(key) => {
+                let step = self.step_by_modifiers(key.modifiers);
+                let page_step = (self.step_num as f64 / 4.0).ceil().max(1.0);
+                match key.key {
+                    KeyVal::Character('\r') | KeyVal::Character('\n') => {
+                        self.start_text_entry();
+                        event_processed!()
+                    }
+                    KeyVal::Special(SpecialKey::Up) |
+                    KeyVal::Special(SpecialKey::Right) => {
+                        self.touch_burst();
+                        self.step_value(step);
+                        event_processed!()
+                    }
+                    KeyVal::Special(SpecialKey::Down) |
+                    KeyVal::Special(SpecialKey::Left) => {
+                        self.touch_burst();
+                        self.step_value(-step);
+                        event_processed!()
+                    }
+                    KeyVal::Special(SpecialKey::PageUp) => {
+                        self.touch_burst();
+                        self.step_value(page_step);
+                        event_processed!()
+                    }
+                    KeyVal::Special(SpecialKey::PageDown) => {
+                        self.touch_burst();
+                        self.step_value(-page_step);
+                        event_processed!()
+                    }
+                    KeyVal::Special(SpecialKey::Home) => {
+                        self.touch_burst();
+                        self.update_value(self.min_value);
+                        event_processed!()
+                    }
+                    KeyVal::Special(SpecialKey::End) => {
+                        self.touch_burst();
+                        self.update_value(self.max_value);
+                        event_processed!()
+                    }
+                    KeyVal::Special(SpecialKey::Delete) => {
+                        self.touch_burst();
+                        self.reset_to_default();
+                        event_processed!()
+                    }
+                    _ => event_not_processed!()
+                }
+            }
             _ => event_not_processed!()
         }.and_then (|p| p.pass_event(ev))
     }
 
+    fn reminder_handler(&mut self) -> bool {
+        let dt = self.reminder_period.unwrap_or(0.0);
+
+        if let Some((tween, target)) = self.value_tween.as_mut() {
+            if tween.advance(dt) {
+                let fraction = tween.value();
+                self.value = self.scale.value_from_fraction(self.min_value, self.max_value, fraction);
+            } else {
+                self.value = *target;
+                self.value_tween = None;
+            }
+            self.ask_for_repaint();
+        }
+
+        if self.hover.is_running() {
+            self.hover.advance(dt);
+            self.ask_for_repaint();
+        }
+
+        if self.gesture == Some(Gesture::Burst) {
+            self.burst_remaining -= dt;
+            if self.burst_remaining <= f64::EPSILON {
+                self.end_gesture();
+            }
+        }
+
+        self.schedule_reminder()
+    }
+
     fn pointer_enter(&mut self) {
-        self.value_indicator_active = true;
+        self.value_indicator_active = self.enabled;
+        if self.enabled {
+            self.hover.retarget(1.0, HOVER_FADE_TIME);
+            self.schedule_reminder();
+        }
         self.ask_for_repaint();
     }
 
     fn pointer_leave(&mut self) {
         self.value_indicator_active = false;
+        if self.hover.target() > 0.0 {
+            self.hover.retarget(0.0, HOVER_FADE_TIME);
+            self.schedule_reminder();
+        }
         self.ask_for_repaint();
     }
 
     fn min_size(&self) -> Size {
-        Size { w: 4. * self.radius, h: 4. * self.radius + 3.0 }
+        let margin = 2. * self.plate_margin;
+        Size { w: 4. * self.radius + margin, h: 4. * self.radius + 3.0 + margin }
+    }
+
+    fn takes_focus(&self) -> bool { self.enabled }
+
+    fn set_focus(&mut self, yn: bool) {
+        if !yn && self.text_entry.is_some() {
+            self.cancel_text_entry();
+        }
+        let hf = self.stub().has_focus;
+        self.stub_mut().has_focus = yn;
+        if hf != yn {
+            self.ask_for_repaint();
+        }
     }
 }
 
 fn refine_step_by_modifiers(modifiers: Modifiers) -> f64 {
     if modifiers.contains(Modifiers::CTRL) {
         1.0
+    } else if modifiers.contains(Modifiers::SHIFT) {
+        1. / 100.
     } else {
         1. / 10.
     }
 }
 
+impl<S: Scale> Enable for Dial<S> {
+    fn set_enabled(&mut self, enabled: bool) {
+        if enabled == self.enabled {
+            return;
+        }
+        self.enabled = enabled;
+        if !enabled {
+            self.text_entry = None;
+            self.drag_origin = None;
+            self.value_indicator_active = false;
+            self.hover = Tween::constant(0.0);
+            self.end_gesture();
+            self.set_focus(false);
+        }
+        self.ask_for_repaint();
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
 pub fn draw_angle_tics<S: Scale>(dial: &Dial<S>, cr: &cairo::Context, num: u32) {
     let rad = dial.radius() * 1.3;
-    let size = dial.min_size();
+    let center = dial.plate_center();
     cr.set_source_rgb(1.,1.,1.);
     cr.set_line_width(1.0);
-    cr.translate(size.w/2., size.h/2. + 4.5);
-    cr.rotate(PI/2.);
-    for _ in 0..num {
-        cr.rotate(PI / (num as f64 + 1.0) * 2.);
-        cr.move_to(rad, 0.0);
-        cr.line_to(rad*1.2, 0.0);
+    cr.translate(center.x, center.y);
+    let intervals = if dial.sweep_angle() >= 360. {
+        num
+    } else {
+        num.max(2) - 1
+    };
+    for i in 0..num {
+        let angle = dial.fraction_angle(i as f64 / intervals as f64);
+        let (sin, cos) = angle.sin_cos();
+        cr.move_to(rad*cos, rad*sin);
+        cr.line_to(rad*1.2*cos, rad*1.2*sin);
         cr.stroke();
     }
 }
+
+/// Draws the dial's step names around it.
+///
+/// The labels reach beyond the dial, reserve room for them with
+/// `Dial::set_plate_margin()`.
+pub fn draw_step_labels<S: Scale>(dial: &Dial<S>, cr: &cairo::Context) {
+    let names = dial.step_names();
+    let intervals = if dial.sweep_angle() >= 360. {
+        names.len()
+    } else {
+        names.len().max(2) - 1
+    };
+
+    let center = dial.plate_center();
+    let rad = dial.radius() * 1.6;
+    cr.set_source_rgb(1., 1., 1.);
+    cr.translate(center.x, center.y);
+
+    let ctx = pangocairo::functions::create_context(&cr).expect("cration of pango context failed");
+    let lyt = pango::Layout::new(&ctx);
+    lyt.set_font_description(Some(&pango::FontDescription::from_string("Sans 8px")));
+
+    for (i, name) in names.iter().enumerate() {
+        lyt.set_text(name);
+        show_layout_at_angle(cr, &lyt, rad, dial.fraction_angle(i as f64 / intervals as f64));
+    }
+}
+
+/// Draws tick marks at the values given by the dial's scale, the
+/// major ones optionally labeled.
+///
+/// The labels reach beyond the dial, reserve room for them with
+/// `Dial::set_plate_margin()`.
+pub fn draw_scale_tics<S: Scale>(dial: &Dial<S>, cr: &cairo::Context, labels: bool) {
+    let rad = dial.radius() * 1.3;
+    let center = dial.plate_center();
+    cr.set_source_rgb(1.,1.,1.);
+    cr.set_line_width(1.0);
+    cr.translate(center.x, center.y);
+
+    let ctx = pangocairo::functions::create_context(&cr).expect("cration of pango context failed");
+    let lyt = pango::Layout::new(&ctx);
+    lyt.set_font_description(Some(&pango::FontDescription::from_string("Sans 8px")));
+
+    let (min, max) = (dial.min_value(), dial.max_value());
+    for tick in dial.scale().ticks(min, max) {
+        let fraction = dial.scale().linear_fraction(min, max, tick.value);
+        if !(0.0..=1.0).contains(&fraction) {
+            continue;
+        }
+        let angle = dial.fraction_angle(fraction);
+        let (sin, cos) = angle.sin_cos();
+        let length = if tick.major { 1.2 } else { 1.1 };
+        cr.move_to(rad*cos, rad*sin);
+        cr.line_to(rad*length*cos, rad*length*sin);
+        cr.stroke();
+
+        if labels && tick.major {
+            lyt.set_text(&tick_label(tick.value));
+            show_layout_at_angle(cr, &lyt, rad * 1.25, angle);
+        }
+    }
+}
+
+fn show_layout_at_angle(cr: &cairo::Context, lyt: &pango::Layout, rad: f64, angle: f64) {
+    let (w, h) = lyt.get_pixel_size();
+    let (w, h): (f64, f64) = (w.into(), h.into());
+    let (sin, cos) = angle.sin_cos();
+    cr.move_to(rad*cos - w/2. * (1. - cos), rad*sin - h/2.);
+    pangocairo::functions::show_layout(cr, lyt);
+}
+
+fn tick_label(value: f64) -> String {
+    if value.is_infinite() {
+        return if value < 0.0 { "-inf".to_string() } else { "inf".to_string() };
+    }
+    let (value, suffix) = if value.abs() >= 1000. { (value / 1000., "k") } else { (value, "") };
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    let text = if text == "-0" { "0" } else { text };
+    format!("{}{}", text, suffix)
+}
+
+#[cfg(all(test, feature="testing"))]
+mod tests {
+    use super::*;
+    use pugl_ui::ui::*;
+    use pugl_ui::layout::stacklayout::*;
+
+    #[test]
+    fn db_scale_limits() {
+        assert_eq!(DbScale.linear_fraction(f64::NEG_INFINITY, 6.0, f64::NEG_INFINITY), 0.0);
+        assert!((DbScale.linear_fraction(f64::NEG_INFINITY, 6.0, 6.0) - 1.0).abs() < 1e-12);
+        assert!((DbScale.linear_fraction(-60.0, 6.0, -60.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn db_scale_step_out_of_minus_inf() {
+        let v = DbScale.step(f64::NEG_INFINITY, 6.0, f64::NEG_INFINITY, 10, 1.0);
+        assert!(v.is_finite());
+        assert!((DbScale.linear_fraction(f64::NEG_INFINITY, 6.0, v) - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn db_scale_step_into_minus_inf() {
+        let v = DbScale.step(f64::NEG_INFINITY, 6.0, -40.0, 10, -10.0);
+        assert_eq!(v, f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn db_scale_step_consistent_with_fraction() {
+        let v = DbScale.step(-60.0, 6.0, 0.0, 10, 1.0);
+        let diff = DbScale.linear_fraction(-60.0, 6.0, v) - DbScale.linear_fraction(-60.0, 6.0, 0.0);
+        assert!((diff - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn skewed_scale_fraction() {
+        assert!((SkewedScale::new(2.0).linear_fraction(0.0, 100.0, 25.0) - 0.5).abs() < 1e-12);
+        assert!((SkewedScale::new(2.0).step(0.0, 100.0, 25.0, 10, 5.0) - 100.0).abs() < 1e-12);
+    }
+
+    fn assert_round_trip(scale: &dyn Scale, min: f64, max: f64) {
+        for i in 0..=10 {
+            let fraction = i as f64 / 10.;
+            let value = scale.value_from_fraction(min, max, fraction);
+            assert!((scale.linear_fraction(min, max, value) - fraction).abs() < 1e-9);
+        }
+    }
+
+    fn assert_step_consistent(scale: &dyn Scale, min: f64, max: f64) {
+        for i in 1..10 {
+            let value = scale.value_from_fraction(min, max, i as f64 / 10.);
+            let stepped = scale.step(min, max, value, 20, 1.0);
+            let diff = scale.linear_fraction(min, max, stepped) - scale.linear_fraction(min, max, value);
+            assert!((diff - 0.05).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn linear_scale_round_trip() {
+        assert_round_trip(&LinearScale, -72.0, 24.0);
+        assert_step_consistent(&LinearScale, -72.0, 24.0);
+    }
+
+    #[test]
+    fn log_scale_round_trip() {
+        assert_round_trip(&LogScale, 0.1, 1000.0);
+        assert_step_consistent(&LogScale, 0.1, 1000.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn skewed_scale_rejects_zero_skew() {
+        SkewedScale::new(0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn skewed_scale_rejects_negative_skew() {
+        SkewedScale::new(-2.0);
+    }
+
+    #[test]
+    fn skewed_scale_round_trip() {
+        assert_round_trip(&SkewedScale::new(2.0), -1.0, 4.0);
+        assert_step_consistent(&SkewedScale::new(2.0), -1.0, 4.0);
+    }
+
+    #[test]
+    fn db_scale_round_trip() {
+        assert_round_trip(&DbScale, f64::NEG_INFINITY, 6.0);
+        assert_round_trip(&DbScale, -60.0, 12.0);
+        assert_step_consistent(&DbScale, -60.0, 12.0);
+    }
+
+    #[test]
+    fn dyn_scale_delegates() {
+        let scale: DynScale = Box::new(LogScale);
+        assert!((scale.linear_fraction(0.1, 1000.0, 10.0) - 0.5).abs() < 1e-12);
+        assert!((scale.value_from_fraction(0.1, 1000.0, 0.25) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn dial_with_dyn_scale() {
+        let logarithmic = true;
+        let scale: DynScale = if logarithmic { Box::new(LogScale) } else { Box::new(LinearScale) };
+        let mut dial = Dial::with_scale(scale, 0.1, 1000.0, 4);
+        dial.set_value(1.0);
+        assert!((dial.value_fraction() - 0.25).abs() < 1e-12);
+        dial.step_value(1.0);
+        assert!((dial.changed_value().unwrap() - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dial_with_dyn_scale_invalid_range() {
+        let scale: DynScale = Box::new(LogScale);
+        Dial::with_scale(scale, 0.0, 1000.0, 4);
+    }
+
+    fn key(key: KeyVal) -> Key {
+        Key { key, modifiers: Modifiers::default(), code: 0 }
+    }
+
+    fn type_text(dial: &mut Dial<LinearScale>, text: &str) {
+        for c in text.chars() {
+            dial.text_entry_key(key(KeyVal::Character(c)));
+        }
+    }
+
+    #[test]
+    fn text_entry_commit() {
+        let mut dial = Dial::<LinearScale>::new(-72.0, 24.0, 32);
+        dial.start_text_entry();
+        dial.text_entry_key(key(KeyVal::Special(SpecialKey::Backspace)));
+        dial.text_entry_key(key(KeyVal::Special(SpecialKey::Backspace)));
+        dial.text_entry_key(key(KeyVal::Special(SpecialKey::Backspace)));
+        dial.text_entry_key(key(KeyVal::Special(SpecialKey::Backspace)));
+        dial.text_entry_key(key(KeyVal::Special(SpecialKey::Backspace)));
+        type_text(&mut dial, "-6.5");
+        dial.text_entry_key(key(KeyVal::Character('\r')));
+        assert!(!dial.is_editing());
+        assert_eq!(dial.changed_value(), Some(-6.5));
+    }
+
+    #[test]
+    fn text_entry_clamps() {
+        let mut dial = Dial::<LinearScale>::new(-72.0, 24.0, 32);
+        dial.text_entry = Some(String::new());
+        type_text(&mut dial, "100");
+        dial.commit_text_entry();
+        assert_eq!(dial.changed_value(), Some(24.0));
+    }
+
+    #[test]
+    fn text_entry_escape_cancels() {
+        let mut dial = Dial::<LinearScale>::new(-72.0, 24.0, 32);
+        dial.text_entry = Some(String::new());
+        type_text(&mut dial, "12");
+        dial.text_entry_key(key(KeyVal::Special(SpecialKey::Escape)));
+        assert!(!dial.is_editing());
+        assert_eq!(dial.changed_value(), None);
+    }
+
+    #[test]
+    fn text_entry_custom_parser() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 20000.0, 32);
+        let unit = String::from("kHz");
+        dial.set_parser(Box::new(move |text: &str| {
+            text.trim().trim_end_matches(unit.as_str()).trim().parse::<f64>().ok().map(|v| v * 1000.)
+        }));
+        dial.text_entry = Some(String::new());
+        type_text(&mut dial, "1.5 kHz");
+        dial.commit_text_entry();
+        assert_eq!(dial.changed_value(), Some(1500.0));
+    }
+
+    fn key_press(dial: &mut Dial<LinearScale>, key: Key) -> Option<Event> {
+        dial.event(Event { data: EventType::KeyPress(key), context: EventContext::default() })
+    }
+
+    #[test]
+    fn keyboard_arrow_steps() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_value(5.0);
+        assert_eq!(key_press(&mut dial, key(KeyVal::Special(SpecialKey::Up))), None);
+        assert!((dial.changed_value().unwrap() - 5.1).abs() < 1e-9);
+        key_press(&mut dial, key(KeyVal::Special(SpecialKey::Left)));
+        assert!((dial.changed_value().unwrap() - 4.9).abs() < 1e-9);
+        key_press(&mut dial, Key { key: KeyVal::Special(SpecialKey::Down), modifiers: Modifiers::CTRL, code: 0 });
+        assert!((dial.changed_value().unwrap() - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn keyboard_page_steps() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 8);
+        dial.set_value(5.0);
+        key_press(&mut dial, key(KeyVal::Special(SpecialKey::PageUp)));
+        assert!((dial.changed_value().unwrap() - 7.5).abs() < 1e-9);
+        key_press(&mut dial, key(KeyVal::Special(SpecialKey::PageDown)));
+        assert!((dial.changed_value().unwrap() - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn keyboard_home_end_delete() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_default_value(3.0);
+        dial.set_value(5.0);
+        key_press(&mut dial, key(KeyVal::Special(SpecialKey::Home)));
+        assert_eq!(dial.changed_value(), Some(0.0));
+        key_press(&mut dial, key(KeyVal::Special(SpecialKey::End)));
+        assert_eq!(dial.changed_value(), Some(10.0));
+        key_press(&mut dial, key(KeyVal::Special(SpecialKey::Delete)));
+        assert_eq!(dial.changed_value(), Some(3.0));
+    }
+
+    #[test]
+    fn keyboard_unknown_key_not_processed() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        assert!(key_press(&mut dial, key(KeyVal::Character('x'))).is_some());
+    }
+
+    fn mouse_button(data: EventType) -> Event {
+        Event { data, context: EventContext::default() }
+    }
+
+    #[test]
+    fn gesture_drag() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        assert!(!dial.gesture_started());
+        dial.event(mouse_button(EventType::MouseButtonPress(MouseButton { num: 1, modifiers: Modifiers::default() })));
+        assert!(dial.gesture_started());
+        assert!(!dial.gesture_started());
+        assert!(!dial.gesture_ended());
+        dial.event(mouse_button(EventType::MouseButtonRelease(MouseButton { num: 1, modifiers: Modifiers::default() })));
+        assert!(dial.gesture_ended());
+        assert!(!dial.gesture_ended());
+    }
+
+    #[test]
+    fn gesture_scroll_burst() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        let scroll = mouse_button(EventType::Scroll(Scroll { dx: 0.0, dy: 1.0, modifiers: Modifiers::default() }));
+        dial.event(scroll);
+        assert!(dial.gesture_started());
+        assert_eq!(dial.reminder_request(), Some(GESTURE_TIMEOUT));
+        dial.event(scroll);
+        assert!(!dial.gesture_started());
+        assert!(!dial.gesture_ended());
+        assert!(!dial.reminder_handler());
+        assert!(dial.gesture_ended());
+        assert!(!dial.gesture_ongoing());
+    }
+
+    #[test]
+    fn animated_set_value() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_value_animation(Some(0.09));
+        dial.set_value(10.0);
+        assert_eq!(dial.value(), 10.0);
+        assert_eq!(dial.value_fraction(), 0.0);
+        assert!(dial.is_animating());
+        assert_eq!(dial.reminder_request(), Some(tween::FRAME_PERIOD));
+
+        let mut last = 0.0;
+        let mut frames = 0;
+        while dial.reminder_handler() {
+            assert!(dial.value_fraction() > last);
+            last = dial.value_fraction();
+            frames += 1;
+        }
+        assert_eq!(frames, 5);
+        assert_eq!(dial.value_fraction(), 1.0);
+        assert!(!dial.is_animating());
+        assert_eq!(dial.changed_value(), None);
+    }
+
+    #[test]
+    fn user_input_finishes_animation() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_value_animation(Some(1.0));
+        dial.set_value(5.0);
+        key_press(&mut dial, key(KeyVal::Special(SpecialKey::Up)));
+        assert!((dial.changed_value().unwrap() - 5.1).abs() < 1e-9);
+        assert!(!dial.is_animating());
+    }
+
+    #[test]
+    fn gesture_burst_timeout_while_animating() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.pointer_enter();
+        assert_eq!(dial.reminder_request(), Some(tween::FRAME_PERIOD));
+        dial.event(mouse_button(EventType::Scroll(Scroll { dx: 0.0, dy: 1.0, modifiers: Modifiers::default() })));
+        assert!(dial.gesture_started());
+        assert_eq!(dial.reminder_request(), None);
+
+        while dial.is_animating() {
+            assert!(dial.reminder_handler());
+        }
+        assert!(!dial.gesture_ended());
+        let remaining = dial.reminder_request().unwrap();
+        assert!(remaining > 0.0 && remaining < GESTURE_TIMEOUT);
+        assert!(!dial.reminder_handler());
+        assert!(dial.gesture_ended());
+    }
+
+    #[test]
+    fn gesture_scroll_during_drag() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.event(mouse_button(EventType::MouseButtonPress(MouseButton { num: 1, modifiers: Modifiers::default() })));
+        dial.event(mouse_button(EventType::Scroll(Scroll { dx: 0.0, dy: 1.0, modifiers: Modifiers::default() })));
+        dial.reminder_handler();
+        assert!(dial.gesture_ongoing());
+        assert!(!dial.gesture_ended());
+    }
+
+    fn drag(dial: &mut Dial<LinearScale>, from: Coord, to: Coord, modifiers: Modifiers) -> Option<f64> {
+        dial.event(Event {
+            data: EventType::MouseButtonPress(MouseButton { num: 1, modifiers }),
+            context: EventContext { pos: from, pos_root: from, ..Default::default() }
+        });
+        dial.event(Event {
+            data: EventType::MouseMove(MotionContext { modifiers, ..Default::default() }),
+            context: EventContext { pos: to, pos_root: to, ..Default::default() }
+        });
+        dial.event(Event {
+            data: EventType::MouseButtonRelease(MouseButton { num: 1, modifiers }),
+            context: EventContext { pos: to, pos_root: to, ..Default::default() }
+        });
+        dial.changed_value()
+    }
+
+    #[test]
+    fn drag_diagonal_default() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        let v = drag(&mut dial, Coord { x: 0., y: 100. }, Coord { x: 10., y: 90. }, Modifiers::default());
+        assert!((v.unwrap() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drag_vertical_sensitivity() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_drag_mode(DragMode::Vertical);
+        dial.set_drag_sensitivity(Some(100.0));
+        let v = drag(&mut dial, Coord { x: 0., y: 100. }, Coord { x: 30., y: 50. }, Modifiers::default());
+        assert!((v.unwrap() - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drag_vertical_fine() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_drag_mode(DragMode::Vertical);
+        dial.set_drag_sensitivity(Some(100.0));
+        let v = drag(&mut dial, Coord { x: 0., y: 100. }, Coord { x: 0., y: 50. }, Modifiers::SHIFT);
+        assert!((v.unwrap() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drag_horizontal_ignores_vertical() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_drag_mode(DragMode::Horizontal);
+        dial.set_drag_sensitivity(Some(100.0));
+        let v = drag(&mut dial, Coord { x: 0., y: 100. }, Coord { x: 0., y: 50. }, Modifiers::default());
+        assert_eq!(v, None);
+        dial.last_click_time = f64::NEG_INFINITY;
+        let v = drag(&mut dial, Coord { x: 0., y: 100. }, Coord { x: 20., y: 100. }, Modifiers::default());
+        assert!((v.unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn modulated_value_repaint() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.needs_repaint();
+        dial.set_modulated_value(Some(3.0));
+        assert!(dial.needs_repaint());
+        dial.set_modulated_value(Some(3.0));
+        assert!(!dial.needs_repaint());
+        assert_eq!(dial.modulated_value(), Some(3.0));
+        assert_eq!(dial.changed_value(), None);
+    }
+
+    #[test]
+    fn linear_ticks_nice_numbers() {
+        let ticks = linear_ticks(0.0, 180.0);
+        assert_eq!(ticks.len(), 19);
+        let majors: Vec<f64> = ticks.iter().filter(|t| t.major).map(|t| t.value).collect();
+        assert_eq!(majors, vec![0.0, 50.0, 100.0, 150.0]);
+    }
+
+    #[test]
+    fn linear_ticks_negative_range() {
+        let majors: Vec<f64> = linear_ticks(-72.0, 24.0).iter().filter(|t| t.major).map(|t| t.value).collect();
+        assert_eq!(majors, vec![-60.0, -40.0, -20.0, 0.0, 20.0]);
+    }
+
+    #[test]
+    fn log_ticks_decades() {
+        let ticks = LogScale.ticks(0.1, 1000.0);
+        let majors: Vec<f64> = ticks.iter().filter(|t| t.major).map(|t| t.value).collect();
+        assert_eq!(majors.len(), 5);
+        assert!((majors[0] - 0.1).abs() < 1e-12);
+        assert!((majors[4] - 1000.0).abs() < 1e-9);
+        assert_eq!(ticks.len(), 4*9 + 1);
+    }
+
+    #[test]
+    fn db_ticks_minus_inf() {
+        let ticks = DbScale.ticks(f64::NEG_INFINITY, 6.0);
+        assert_eq!(ticks[0], Tick { value: f64::NEG_INFINITY, major: true });
+        assert!(ticks.iter().skip(1).all(|t| t.value.is_finite()));
+    }
+
+    #[test]
+    fn tick_labels() {
+        assert_eq!(tick_label(0.1), "0.1");
+        assert_eq!(tick_label(100.0), "100");
+        assert_eq!(tick_label(2000.0), "2k");
+        assert_eq!(tick_label(-0.0000001), "0");
+        assert_eq!(tick_label(f64::NEG_INFINITY), "-inf");
+    }
+
+    #[test]
+    fn endless_wraps_past_max() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 360.0, 36);
+        dial.set_endless(true);
+        assert_eq!(dial.sweep_angle(), 360.);
+        dial.set_value(355.0);
+        dial.step_value(1.0);
+        assert!((dial.changed_value().unwrap() - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn endless_wraps_past_min() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 360.0, 36);
+        dial.set_endless(true);
+        dial.set_value(5.0);
+        dial.step_value(-1.0);
+        assert!((dial.changed_value().unwrap() - 355.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn endless_db_scale_wraps_past_min() {
+        let mut dial = Dial::<DbScale>::new(-60.0, 6.0, 10);
+        dial.set_endless(true);
+        dial.set_value(-60.0);
+        dial.step_value(-1.0);
+        let expected = DbScale.value_from_fraction(-60.0, 6.0, 0.9);
+        assert!((dial.changed_value().unwrap() - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn endless_skewed_scale_wraps_past_min() {
+        let mut dial = Dial::with_scale(SkewedScale::new(2.0), 0.0, 4.0, 8);
+        dial.set_endless(true);
+        dial.set_value(0.0);
+        dial.step_value(-2.0);
+        assert!((dial.changed_value().unwrap() - 4.0 * 0.75f64.powi(2)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn set_endless_restores_sweep() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 360.0, 36);
+        dial.set_angle_range(90., 270.);
+        dial.set_endless(true);
+        dial.set_endless(true);
+        assert_eq!(dial.sweep_angle(), 360.);
+        dial.set_endless(false);
+        assert_eq!(dial.sweep_angle(), 270.);
+        assert_eq!(dial.start_angle(), 90.);
+        dial.set_endless(false);
+        assert_eq!(dial.sweep_angle(), 270.);
+    }
+
+    #[test]
+    fn not_endless_clamps() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 360.0, 36);
+        dial.set_value(355.0);
+        dial.step_value(1.0);
+        assert_eq!(dial.changed_value(), Some(360.0));
+    }
+
+    #[test]
+    fn disabled_dial_ignores_events() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_enabled(false);
+        assert!(!dial.takes_focus());
+        assert!(key_press(&mut dial, key(KeyVal::Special(SpecialKey::End))).is_some());
+        assert_eq!(dial.changed_value(), None);
+        dial.set_enabled(true);
+        assert!(dial.takes_focus());
+        assert!(key_press(&mut dial, key(KeyVal::Special(SpecialKey::End))).is_none());
+        assert_eq!(dial.changed_value(), Some(10.0));
+    }
+
+    #[test]
+    fn disabling_dial_ends_gesture() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.event(mouse_button(EventType::MouseButtonPress(MouseButton { num: 1, modifiers: Modifiers::default() })));
+        assert!(dial.gesture_started());
+        dial.set_enabled(false);
+        assert!(dial.gesture_ended());
+        assert!(!dial.has_focus());
+    }
+
+    #[test]
+    fn discrete_dial_snaps() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 3.0, 10);
+        dial.set_step_names(&["sine", "saw", "square", "noise"]);
+        assert_eq!(dial.step_num(), 3);
+        dial.update_value(1.4);
+        assert_eq!(dial.changed_value(), Some(1.0));
+        dial.set_value(2.0);
+        assert_eq!(dial.value_text(), "square");
+    }
+
+    #[test]
+    fn discrete_dial_accumulates_steps() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 3.0, 3);
+        dial.set_discrete(true);
+        dial.step_value(0.6);
+        assert_eq!(dial.changed_value(), None);
+        dial.step_value(0.6);
+        assert_eq!(dial.changed_value(), Some(1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn db_scale_infinite_max() {
+        DbScale.panic_if_invalid_range(0.0, f64::INFINITY);
+    }
+
+    fn center_offset(dial: &Dial<LinearScale>, dx: f64, dy: f64) -> Coord {
+        dial.center() + Coord { x: dx, y: dy }
+    }
+
+    fn assert_fraction(fraction: Option<f64>, expected: f64) {
+        assert!((fraction.unwrap() - expected).abs() < 1e-9, "{:?} != {}", fraction, expected);
+    }
+
+    #[test]
+    fn set_angle_range_clamps_sweep() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_angle_range(180., 400.);
+        assert_eq!(dial.start_angle(), 180.);
+        assert_eq!(dial.sweep_angle(), 360.);
+        dial.set_angle_range(180., -10.);
+        assert_eq!(dial.sweep_angle(), 0.);
+        assert_eq!(dial.fraction_at(center_offset(&dial, 0., -10.)), None);
+    }
+
+    #[test]
+    fn fraction_angle_custom_range() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_angle_range(180., 180.);
+        assert!((dial.fraction_angle(0.0) - PI).abs() < 1e-12);
+        assert!((dial.fraction_angle(0.5) - 1.5 * PI).abs() < 1e-12);
+        assert!((dial.fraction_angle(1.0) - 2. * PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn fraction_at_custom_range() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_angle_range(180., 180.);
+        assert_fraction(dial.fraction_at(center_offset(&dial, -10., 0.)), 0.0);
+        assert_fraction(dial.fraction_at(center_offset(&dial, 0., -10.)), 0.5);
+        assert_fraction(dial.fraction_at(center_offset(&dial, 10., 0.)), 1.0);
+        assert_eq!(dial.fraction_at(center_offset(&dial, 0., 10.)), None);
+        assert_eq!(dial.fraction_at(center_offset(&dial, 10., 1.)), None);
+        assert_eq!(dial.fraction_at(dial.center()), None);
+    }
+
+    #[test]
+    fn fraction_at_full_circle() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_angle_range(90., 360.);
+        assert_fraction(dial.fraction_at(center_offset(&dial, 0., 10.)), 0.0);
+        assert_fraction(dial.fraction_at(center_offset(&dial, -10., 0.)), 0.25);
+        assert_fraction(dial.fraction_at(center_offset(&dial, 0., -10.)), 0.5);
+        assert_fraction(dial.fraction_at(center_offset(&dial, 10., 0.)), 0.75);
+    }
+
+    #[test]
+    fn is_on_ring() {
+        let dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        assert!(dial.is_on_ring(center_offset(&dial, 0., -18.)));
+        assert!(dial.is_on_ring(center_offset(&dial, 15., 0.)));
+        assert!(dial.is_on_ring(center_offset(&dial, 0., 21.)));
+        assert!(!dial.is_on_ring(center_offset(&dial, 0., -14.)));
+        assert!(!dial.is_on_ring(center_offset(&dial, 22., 0.)));
+        assert!(!dial.is_on_ring(dial.center()));
+    }
+
+    #[test]
+    fn click_on_ring_relative() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        assert!(!dial.is_absolute_positioning());
+        let top = center_offset(&dial, 0., -18.);
+        assert_eq!(drag(&mut dial, top, top, Modifiers::default()), None);
+        assert_eq!(dial.value(), 0.0);
+    }
+
+    #[test]
+    fn click_on_ring_absolute() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_absolute_positioning(true);
+        let top = center_offset(&dial, 0., -18.);
+        assert!((drag(&mut dial, top, top, Modifiers::default()).unwrap() - 5.0).abs() < 1e-9);
+
+        dial.last_click_time = f64::NEG_INFINITY;
+        let inner = center_offset(&dial, 0., -10.);
+        assert_eq!(drag(&mut dial, inner, inner, Modifiers::default()), None);
+    }
+
+    #[derive(Default)]
+    struct RootWidget {
+        stub: WidgetStub
+    }
+
+    impl Widget for RootWidget {
+        widget_stub!();
+    }
+
+    fn dial_view(dial: Box<Dial<LinearScale>>) -> (Box<PuglView<UI<RootWidget>>>, WidgetHandle<Dial<LinearScale>>) {
+        let rw = Box::new(RootWidget::default());
+        let mut view = PuglView::new(std::ptr::null_mut(), |pv| UI::new_scaled(pv, rw, 1.0));
+
+        let ui = view.handle();
+        let dial = ui.new_widget(dial);
+        ui.pack_to_layout(dial, ui.root_layout(), StackDirection::Front);
+        ui.do_layout();
+        ui.show_window();
+
+        (view, dial)
+    }
+
+    fn queue_and_update(view: &mut PuglView<UI<RootWidget>>, data: EventType, context: EventContext) {
+        view.queue_event(Event { data, context });
+        view.handle().update(-1.0);
+    }
+
+    fn double_click(view: &mut PuglView<UI<RootWidget>>, dial: WidgetHandle<Dial<LinearScale>>) {
+        let pos = view.handle().widget(dial).center();
+        let button = MouseButton { num: 1, modifiers: Modifiers::default() };
+        for time in &[1.0, 1.2] {
+            let context = EventContext { pos, pos_root: pos, time: *time };
+            queue_and_update(view, EventType::MouseButtonPress(button), context);
+            queue_and_update(view, EventType::MouseButtonRelease(button), context);
+        }
+    }
+
+    fn type_keys(view: &mut PuglView<UI<RootWidget>>, keys: &[KeyVal]) {
+        for k in keys {
+            queue_and_update(view, EventType::KeyPress(key(*k)), EventContext::default());
+        }
+    }
+
+    #[test]
+    fn double_click_text_entry_focused() {
+        let (mut view, dial) = dial_view(Dial::new(0.0, 10.0, 10));
+        view.handle().focus_widget(dial);
+        double_click(&mut view, dial);
+        assert!(view.handle().widget(dial).is_editing());
+        assert!(!view.handle().widget(dial).wants_focus());
+
+        let mut keys = vec![KeyVal::Special(SpecialKey::Backspace); 8];
+        keys.extend_from_slice(&[KeyVal::Character('7'), KeyVal::Character('\r')]);
+        type_keys(&mut view, &keys);
+
+        let w = view.handle().widget(dial);
+        assert!(!w.is_editing());
+        assert_eq!(w.changed_value(), Some(7.0));
+    }
+
+    #[test]
+    fn double_click_text_entry_unfocused() {
+        let (mut view, dial) = dial_view(Dial::new(0.0, 10.0, 10));
+        double_click(&mut view, dial);
+        let ui = view.handle();
+        assert!(ui.widget(dial).is_editing());
+        assert!(ui.widget(dial).wants_focus());
+        assert!(!ui.widget(dial).wants_focus());
+        ui.focus_widget(dial);
+        assert!(ui.widget(dial).is_editing());
+
+        let mut keys = vec![KeyVal::Special(SpecialKey::Backspace); 8];
+        keys.extend_from_slice(&[KeyVal::Character('7'), KeyVal::Character('\r')]);
+        type_keys(&mut view, &keys);
+        assert_eq!(view.handle().widget(dial).changed_value(), Some(7.0));
+    }
+
+    #[test]
+    fn losing_focus_cancels_text_entry() {
+        let (mut view, dial) = dial_view(Dial::new(0.0, 10.0, 10));
+        view.handle().focus_widget(dial);
+        double_click(&mut view, dial);
+        type_keys(&mut view, &[KeyVal::Character('7')]);
+
+        let w = view.handle().widget(dial);
+        w.set_focus(false);
+        assert!(!w.is_editing());
+        assert_eq!(w.changed_value(), None);
+    }
+
+    #[test]
+    fn disabling_dial_cancels_text_entry() {
+        let (mut view, dial) = dial_view(Dial::new(0.0, 10.0, 10));
+        view.handle().focus_widget(dial);
+        double_click(&mut view, dial);
+
+        let w = view.handle().widget(dial);
+        w.set_enabled(false);
+        assert!(!w.is_editing());
+    }
+
+    #[test]
+    fn drag_circular() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_drag_mode(DragMode::Circular);
+        let left = center_offset(&dial, -10., 0.);
+        let top = center_offset(&dial, 0., -10.);
+        let v = drag(&mut dial, left, top, Modifiers::default());
+        assert!((v.unwrap() - 3.0).abs() < 1e-9);
+        dial.set_value(v.unwrap());
+
+        dial.last_click_time = f64::NEG_INFINITY;
+        let v = drag(&mut dial, top, left, Modifiers::SHIFT);
+        assert!((v.unwrap() - 2.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drag_circular_rejects_wrap() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_drag_mode(DragMode::Circular);
+        dial.set_angle_range(90., 360.);
+        dial.set_value(5.0);
+        let before_start = center_offset(&dial, 1., 10.);
+        let after_start = center_offset(&dial, -1., 10.);
+        assert_eq!(drag(&mut dial, after_start, before_start, Modifiers::default()), None);
+        assert_eq!(dial.value(), 5.0);
+    }
+
+    #[test]
+    fn drag_circular_endless_wraps() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_drag_mode(DragMode::Circular);
+        dial.set_angle_range(90., 360.);
+        dial.set_endless(true);
+        dial.set_value(5.0);
+        let before_start = center_offset(&dial, 1., 10.);
+        let after_start = center_offset(&dial, -1., 10.);
+        let v = drag(&mut dial, after_start, before_start, Modifiers::default()).unwrap();
+        let expected = 5.0 - 10.0 * 2.0 * (0.1f64).atan() / (2.0 * PI);
+        assert!((v - expected).abs() < 1e-9);
+    }
+
+    fn render_dial<S: Scale>(dial: &mut Dial<S>) -> cairo::ImageSurface {
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 80, 80).unwrap();
+        {
+            let cr = cairo::Context::new(&surface);
+            dial.exposed(&ExposeArea { pos: Coord::default(), size: Size { w: 80., h: 80. } }, &cr);
+        }
+        surface
+    }
+
+    /// Returns `[r, g, b, a]` of the pixel containing `pos`
+    fn pixel(surface: &mut cairo::ImageSurface, pos: Coord) -> [u8; 4] {
+        let stride = surface.get_stride() as usize;
+        let data = surface.get_data().unwrap();
+        let i = pos.y.floor() as usize * stride + pos.x.floor() as usize * 4;
+        [data[i + 2], data[i + 1], data[i], data[i + 3]]
+    }
+
+    fn ring_point<S: Scale>(dial: &Dial<S>, fraction: f64, radius: f64) -> Coord {
+        let (sin, cos) = dial.fraction_angle(fraction).sin_cos();
+        dial.center() + Coord { x: radius * cos, y: radius * sin }
+    }
+
+    fn is_arc_colored(p: [u8; 4]) -> bool {
+        p[0] > 100 && p[1] < 20 && p[2] < 20 && p[3] == 255
+    }
+
+    fn is_black(p: [u8; 4]) -> bool {
+        p[0] < 20 && p[1] < 20 && p[2] < 20 && p[3] == 255
+    }
+
+    #[test]
+    fn bipolar_arc_from_zero() {
+        let mut dial = Dial::<LinearScale>::new(-72.0, 24.0, 32);
+        dial.set_hue(Some(0.0));
+        dial.set_arc_origin(Some(0.0));
+        let r = dial.radius();
+
+        dial.set_value(-24.0);
+        let mut surface = render_dial(&mut dial);
+        assert!(is_arc_colored(pixel(&mut surface, ring_point(&dial, 0.625, r))));
+        assert!(is_black(pixel(&mut surface, ring_point(&dial, 0.3, r))));
+        assert!(is_black(pixel(&mut surface, ring_point(&dial, 0.85, r))));
+
+        dial.set_value(24.0);
+        let mut surface = render_dial(&mut dial);
+        assert!(is_arc_colored(pixel(&mut surface, ring_point(&dial, 0.875, r))));
+        assert!(is_black(pixel(&mut surface, ring_point(&dial, 0.625, r))));
+    }
+
+    #[test]
+    fn log_scale_arc_from_one() {
+        let mut dial = Dial::<LogScale>::new(0.1, 1000.0, 4);
+        dial.set_hue(Some(0.0));
+        dial.set_arc_origin(Some(1.0));
+        dial.set_value(100.0);
+        let r = dial.radius();
+
+        let mut surface = render_dial(&mut dial);
+        assert!(is_arc_colored(pixel(&mut surface, ring_point(&dial, 0.5, r))));
+        assert!(is_black(pixel(&mut surface, ring_point(&dial, 0.15, r))));
+        assert!(is_black(pixel(&mut surface, ring_point(&dial, 0.9, r))));
+    }
+
+    #[test]
+    fn no_arc_without_origin() {
+        let mut dial = Dial::<LinearScale>::new(-72.0, 24.0, 32);
+        dial.set_hue(Some(0.0));
+        dial.set_value(-24.0);
+        let r = dial.radius();
+
+        let mut surface = render_dial(&mut dial);
+        assert!(is_black(pixel(&mut surface, ring_point(&dial, 0.625, r))));
+    }
+
+    fn render_plate(draw: impl FnOnce(&cairo::Context)) -> cairo::ImageSurface {
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 80, 80).unwrap();
+        {
+            let cr = cairo::Context::new(&surface);
+            draw(&cr);
+        }
+        surface
+    }
+
+    fn drawn_in(surface: &mut cairo::ImageSurface, from: Coord, to: Coord) -> bool {
+        let (x0, y0, x1, y1) = (from.x.floor() as i32, from.y.floor() as i32, to.x.ceil() as i32, to.y.ceil() as i32);
+        (y0..y1).any(|y| (x0..x1).any(|x| pixel(surface, Coord { x: x.into(), y: y.into() })[3] > 100))
+    }
+
+    fn drawn_near(surface: &mut cairo::ImageSurface, pos: Coord) -> bool {
+        drawn_in(surface, pos + Coord { x: -1., y: -1. }, pos + Coord { x: 1., y: 1. })
+    }
+
+    #[test]
+    fn angle_tics_custom_range() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_angle_range(180., 180.);
+        let r = dial.radius() * 1.3 * 1.1;
+
+        let mut surface = render_plate(|cr| draw_angle_tics(&dial, cr, 3));
+        assert!(drawn_near(&mut surface, ring_point(&dial, 0.0, r)));
+        assert!(drawn_near(&mut surface, ring_point(&dial, 0.5, r)));
+        assert!(drawn_near(&mut surface, ring_point(&dial, 1.0, r)));
+        assert!(!drawn_near(&mut surface, ring_point(&dial, 0.25, r)));
+        assert!(!drawn_near(&mut surface, dial.center() + Coord { x: 0., y: r }));
+    }
+
+    #[test]
+    fn step_labels_custom_range() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 2.0, 2);
+        dial.set_step_names(&["a", "b", "c"]);
+        dial.set_angle_range(180., 180.);
+        let (c, r) = (dial.center(), dial.radius() * 1.6);
+
+        let mut surface = render_plate(|cr| draw_step_labels(&dial, cr));
+        assert!(drawn_in(&mut surface, c + Coord { x: -r - 10., y: -6. }, c + Coord { x: -r, y: 6. }));
+        assert!(drawn_in(&mut surface, c + Coord { x: -5., y: -r - 6. }, c + Coord { x: 5., y: -r + 6. }));
+        assert!(drawn_in(&mut surface, c + Coord { x: r, y: -6. }, c + Coord { x: r + 10., y: 6. }));
+        assert!(!drawn_in(&mut surface, c + Coord { x: -r, y: 8. }, c + Coord { x: r, y: r + 8. }));
+    }
+
+    #[test]
+    fn modulation_arc_and_dot() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_hue(Some(0.0));
+        dial.set_value(2.0);
+        let r = dial.radius() * 1.25;
+        let is_modulation_colored = |p: [u8; 4]| p[0] > 200 && p[1] > 100 && p[1] < 160 && p[3] > 200;
+
+        let mut surface = render_dial(&mut dial);
+        assert!(!drawn_near(&mut surface, ring_point(&dial, 0.5, r)));
+
+        dial.set_modulated_value(Some(8.0));
+        let mut surface = render_dial(&mut dial);
+        assert!(is_modulation_colored(pixel(&mut surface, ring_point(&dial, 0.5, r))));
+        assert!(is_modulation_colored(pixel(&mut surface, ring_point(&dial, 0.8, r))));
+        assert!(!drawn_near(&mut surface, ring_point(&dial, 0.1, r)));
+        assert!(!drawn_near(&mut surface, ring_point(&dial, 0.9, r)));
+    }
+
+    #[test]
+    fn plate_margin_enlarges_dial() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        let (size, center) = (dial.min_size(), dial.center());
+        dial.set_plate_margin(10.0);
+        assert_eq!(dial.min_size(), Size { w: size.w + 20., h: size.h + 20. });
+        assert_eq!(dial.center(), center + Coord { x: 10., y: 10. });
+        dial.set_plate_margin(-5.0);
+        assert_eq!(dial.plate_margin(), 0.0);
+    }
+
+    #[test]
+    fn scale_tics_custom_range() {
+        let mut dial = Dial::<LinearScale>::new(0.0, 10.0, 10);
+        dial.set_angle_range(180., 180.);
+        dial.set_plate_margin(4.0);
+        let r = dial.radius() * 1.3 * 1.05;
+
+        let mut surface = render_plate(|cr| draw_scale_tics(&dial, cr, false));
+        assert!(drawn_near(&mut surface, ring_point(&dial, 0.0, r)));
+        assert!(drawn_near(&mut surface, ring_point(&dial, 0.4, r)));
+        assert!(drawn_near(&mut surface, ring_point(&dial, 0.5, r)));
+        assert!(drawn_near(&mut surface, ring_point(&dial, 1.0, r)));
+        assert!(!drawn_near(&mut surface, dial.center() + Coord { x: 0., y: r }));
+        assert!(!drawn_in(&mut surface, dial.center() + Coord { x: -r, y: 5. }, dial.center() + Coord { x: r, y: r + 5. }));
+    }
+
+    #[test]
+    fn plate_is_clipped_to_widget() {
+        let mut dial = Dial::<LogScale>::new(0.1, 1000.0, 4);
+        dial.set_plate_draw(Box::new(|d: &Dial<LogScale>, cr: &cairo::Context| draw_scale_tics(d, cr, true)));
+        let size = dial.min_size();
+        dial.set_size(&size);
+
+        let mut surface = render_dial(&mut dial);
+        assert!(!drawn_in(&mut surface, Coord { x: size.w, y: 0. }, Coord { x: 80., y: size.h }));
+    }
+}
@@ -0,0 +1,15 @@
+use pugl_ui::widget::Widget;
+
+/// Widgets that can be disabled
+///
+/// A disabled widget ignores user events, does not take the focus
+/// and is drawn desaturated.
+///
+/// Disabling a widget clears its own focus state, but the `UI` still
+/// routes key events to it as its focused widget. So after disabling
+/// the focused widget the application should move the focus on by
+/// `UI::focus_next_widget()`, which skips disabled widgets.
+pub trait Enable: Widget {
+    fn set_enabled(&mut self, enabled: bool);
+    fn is_enabled(&self) -> bool;
+}
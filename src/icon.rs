@@ -0,0 +1,256 @@
+use std::fmt;
+use std::os::raw::c_int;
+
+use pugl_sys::{Coord, Size};
+
+/// An icon to be shown on a `Button`
+///
+/// Icons are either decoded from PNG data, e.g. embedded by
+/// `include_bytes!()`, or drawn by a closure using cairo.
+///
+/// With the `rsvg` feature enabled, icons can also be rendered from
+/// SVG data by librsvg, which then needs to be installed.
+pub struct Icon {
+    size: Size,
+    source: IconSource,
+}
+
+enum IconSource {
+    Pixels {
+        data: Vec<u8>,
+        format: cairo::Format,
+        width: i32,
+        height: i32,
+        stride: i32
+    },
+    Drawing(Box<dyn Fn(&cairo::Context, Size) + Send + Sync>),
+}
+
+/// The error returned if an icon can not be created
+#[derive(Debug)]
+pub enum IconError {
+    /// The PNG data could not be decoded
+    Png(cairo::IoError),
+    /// The SVG data could not be parsed or rendered
+    Svg,
+    /// The decoded pixels could not be accessed
+    Cairo(cairo::BorrowError),
+}
+
+impl fmt::Display for IconError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IconError::Png(e) => write!(f, "invalid PNG data: {}", e),
+            IconError::Svg => write!(f, "invalid SVG data"),
+            IconError::Cairo(e) => write!(f, "cairo error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for IconError {}
+
+impl Icon {
+    /// Decodes the icon from the contents of a PNG file.
+    ///
+    /// The size of the icon is the pixel size of the image.
+    pub fn from_png(png: &[u8]) -> Result<Icon, IconError> {
+        let mut reader = png;
+        let surface = cairo::ImageSurface::create_from_png(&mut reader).map_err(IconError::Png)?;
+        let size = Size { w: surface.get_width().into(), h: surface.get_height().into() };
+        Self::from_surface(&surface, size)
+    }
+
+    /// Renders the icon from the contents of an SVG file scaled to `size`.
+    #[cfg(feature = "rsvg")]
+    pub fn from_svg(svg: &[u8], size: Size) -> Result<Icon, IconError> {
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, size.w.ceil() as i32, size.h.ceil() as i32)
+            .map_err(|e| IconError::Cairo(e.into()))?;
+        if !rsvg::render(svg, &cairo::Context::new(&surface), size) {
+            return Err(IconError::Svg);
+        }
+        Self::from_surface(&surface, size)
+    }
+
+    fn from_surface(surface: &cairo::ImageSurface, size: Size) -> Result<Icon, IconError> {
+        let mut data = Vec::new();
+        surface.with_data(|d| data.extend_from_slice(d)).map_err(IconError::Cairo)?;
+        Ok(Icon {
+            size,
+            source: IconSource::Pixels {
+                data,
+                format: surface.get_format(),
+                width: surface.get_width(),
+                height: surface.get_height(),
+                stride: surface.get_stride()
+            }
+        })
+    }
+
+    /// An icon of `size` drawn by `draw`.
+    ///
+    /// The closure draws into the rectangle from `(0, 0)` to `size`.
+    /// The source color is already set to the color of the button's
+    /// text when the closure is called.
+    pub fn from_drawing(size: Size, draw: Box<dyn Fn(&cairo::Context, Size) + Send + Sync>) -> Icon {
+        Icon { size, source: IconSource::Drawing(draw) }
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Renders the icon with its upper left corner at `pos`.
+    ///
+    /// Image icons are painted with `alpha`, drawings use the current
+    /// source of `cr`.
+    pub fn render(&self, cr: &cairo::Context, pos: Coord, alpha: f64) {
+        cr.save();
+        cr.translate(pos.x, pos.y);
+        let mut borrowed = None;
+        match &self.source {
+            IconSource::Pixels { data, format, width, height, stride } => {
+                let format: c_int = (*format).into();
+                // The surface only borrows `data`. Cairo does not write to
+                // source surfaces, and finishing the surface below makes
+                // cairo copy the data into snapshots that the target, e.g.
+                // an SVG surface, may still hold.
+                let surface = unsafe {
+                    cairo::ImageSurface::from_raw_full(cairo_sys::cairo_image_surface_create_for_data(
+                        data.as_ptr() as *mut u8, format, *width, *height, *stride))
+                };
+                if let Ok(surface) = surface {
+                    cr.set_source_surface(&surface, 0.0, 0.0);
+                    cr.rectangle(0.0, 0.0, self.size.w, self.size.h);
+                    cr.clip();
+                    cr.paint_with_alpha(alpha);
+                    borrowed = Some(surface);
+                }
+            }
+            IconSource::Drawing(draw) => draw(cr, self.size)
+        }
+        cr.restore();
+        if let Some(surface) = borrowed {
+            surface.finish();
+        }
+    }
+}
+
+#[cfg(feature = "rsvg")]
+mod rsvg {
+    use std::os::raw::{c_double, c_int, c_void};
+    use std::ptr;
+
+    use pugl_sys::Size;
+
+    #[repr(C)]
+    struct RsvgRectangle {
+        x: c_double,
+        y: c_double,
+        width: c_double,
+        height: c_double,
+    }
+
+    // Linked by the build script as found by pkg-config
+    extern "C" {
+        fn rsvg_handle_new_from_data(data: *const u8, data_len: usize, error: *mut *mut c_void) -> *mut c_void;
+        fn rsvg_handle_render_document(handle: *mut c_void,
+                                       cr: *mut cairo_sys::cairo_t,
+                                       viewport: *const RsvgRectangle,
+                                       error: *mut *mut c_void) -> c_int;
+        fn g_object_unref(object: *mut c_void);
+    }
+
+    /// Renders `svg` fit into `size` into `cr`. Returns `false` on failure.
+    pub fn render(svg: &[u8], cr: &cairo::Context, size: Size) -> bool {
+        let viewport = RsvgRectangle { x: 0.0, y: 0.0, width: size.w, height: size.h };
+        unsafe {
+            let handle = rsvg_handle_new_from_data(svg.as_ptr(), svg.len(), ptr::null_mut());
+            if handle.is_null() {
+                return false;
+            }
+            let success = rsvg_handle_render_document(handle, cr.to_raw_none(), &viewport, ptr::null_mut()) != 0;
+            g_object_unref(handle);
+            success
+        }
+    }
+}
+
+#[cfg(all(test, feature="testing"))]
+mod tests {
+    use super::*;
+
+    fn png_data(w: i32, h: i32) -> Vec<u8> {
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, w, h).unwrap();
+        {
+            let cr = cairo::Context::new(&surface);
+            cr.set_source_rgb(1.0, 0.0, 0.0);
+            cr.paint();
+        }
+        let mut png = Vec::new();
+        surface.write_to_png(&mut png).unwrap();
+        png
+    }
+
+    #[test]
+    fn icon_from_png() {
+        let icon = Icon::from_png(&png_data(16, 12)).unwrap();
+        assert_eq!(icon.size(), Size { w: 16.0, h: 12.0 });
+
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 20, 20).unwrap();
+        {
+            let cr = cairo::Context::new(&surface);
+            icon.render(&cr, Coord { x: 2.0, y: 2.0 }, 1.0);
+        }
+        let mut surface = surface;
+        let stride = surface.get_stride() as usize;
+        let data = surface.get_data().unwrap();
+        let pixel = |x: usize, y: usize| &data[y * stride + x * 4 .. y * stride + x * 4 + 4];
+        assert_eq!(pixel(0, 0), &[0, 0, 0, 0]);
+        assert_eq!(pixel(5, 5)[2], 255);
+        assert_eq!(pixel(5, 5)[3], 255);
+        assert_eq!(pixel(18, 5), &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn icon_from_invalid_png() {
+        assert!(Icon::from_png(b"no png").is_err());
+    }
+
+    #[test]
+    fn icon_from_drawing() {
+        let icon = Icon::from_drawing(Size { w: 10.0, h: 8.0 }, Box::new(|cr: &cairo::Context, size: Size| {
+            cr.rectangle(0.0, 0.0, size.w, size.h);
+            cr.fill();
+        }));
+        assert_eq!(icon.size(), Size { w: 10.0, h: 8.0 });
+    }
+
+    #[test]
+    fn icon_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Icon>();
+    }
+
+    #[test]
+    fn icon_from_svg() {
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="8" height="8"><rect width="8" height="8" fill="#ff0000"/></svg>"##;
+        let icon = Icon::from_svg(svg, Size { w: 16.0, h: 12.0 }).unwrap();
+        assert_eq!(icon.size(), Size { w: 16.0, h: 12.0 });
+
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, 20, 20).unwrap();
+        {
+            let cr = cairo::Context::new(&surface);
+            icon.render(&cr, Coord { x: 2.0, y: 2.0 }, 1.0);
+        }
+        let mut surface = surface;
+        let stride = surface.get_stride() as usize;
+        let data = surface.get_data().unwrap();
+        assert_eq!(data[5 * stride + 5 * 4 + 2], 255);
+        assert_eq!(data[5 * stride + 18 * 4 + 3], 0);
+    }
+
+    #[test]
+    fn icon_from_invalid_svg() {
+        assert!(Icon::from_svg(b"no svg", Size { w: 16.0, h: 16.0 }).is_err());
+    }
+}
@@ -7,25 +7,153 @@ use crate::utils;
 pub struct Label {
     stub: WidgetStub,
     text: String,
-    min_size: Size
+    min_size: Size,
+    needs_relayout: bool,
+
+    font: Option<String>,
+    color: utils::RGB,
+    markup: bool,
+
+    h_align: utils::Align,
+    v_align: utils::Align,
+    min_width: Option<f64>,
+    width_expandable: bool,
+    height_expandable: bool,
 }
 
 impl Label {
     pub fn new(text: &str) -> Box<Label> {
-        let sf = cairo::ImageSurface::create (cairo::Format::ARgb32, 8, 8).unwrap();
-        let cr = cairo::Context::new (&sf);
-
-        let lyt = utils::pango_layout(text, &cr);
+        Self::create(text, false)
+    }
 
-        let (w, h) = lyt.get_pixel_size();
-        let (w, h): (f64, f64) = (w.into(), h.into());
-        let min_size = Size { w, h };
+    /// A label showing `markup` in Pango markup language, e.g.
+    /// `"-3 <small>dB</small>"` or `"10<sup>3</sup>"`
+    pub fn new_markup(markup: &str) -> Box<Label> {
+        Self::create(markup, true)
+    }
 
-        Box::new(Label {
+    fn create(text: &str, markup: bool) -> Box<Label> {
+        let mut label = Box::new(Label {
             stub: WidgetStub::default(),
             text: text.to_string(),
-            min_size
-        })
+            min_size: Size::default(),
+            needs_relayout: false,
+            font: None,
+            color: (1., 1., 1.),
+            markup,
+            h_align: utils::Align::Start,
+            v_align: utils::Align::Start,
+            min_width: None,
+            width_expandable: false,
+            height_expandable: false,
+        });
+        label.min_size = label.measure();
+        label
+    }
+
+    /// Changes the text and re-measures the label.
+    ///
+    /// As widgets can not trigger a new layout by themselves, the
+    /// application should check `needs_relayout()` and then call
+    /// `UI::do_layout()`.
+    pub fn set_text(&mut self, text: &str) {
+        if text == self.text {
+            return;
+        }
+        self.text = text.to_string();
+        self.remeasure();
+    }
+
+    /// Sets the font by a Pango font description string like
+    /// `"Sans Bold 10px"`. `None` resets to the default font.
+    pub fn set_font(&mut self, font: Option<&str>) {
+        self.font = font.map(String::from);
+        self.remeasure();
+    }
+
+    pub fn font(&self) -> Option<&str> {
+        self.font.as_deref()
+    }
+
+    /// Sets the text color, default is white.
+    pub fn set_color(&mut self, color: utils::RGB) {
+        self.color = color;
+        self.ask_for_repaint();
+    }
+
+    pub fn color(&self) -> utils::RGB {
+        self.color
+    }
+
+    /// If `markup` is `true`, the text is interpreted as Pango markup.
+    pub fn set_markup(&mut self, markup: bool) {
+        if markup != self.markup {
+            self.markup = markup;
+            self.remeasure();
+        }
+    }
+
+    pub fn is_markup(&self) -> bool {
+        self.markup
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns `true` once if the label has grown by changing its text
+    /// or font
+    pub fn needs_relayout(&mut self) -> bool {
+        let needs_relayout = self.needs_relayout;
+        self.needs_relayout = false;
+        needs_relayout
+    }
+
+    /// Sets the alignment of the text if the label is larger than its
+    /// minimal size. Default is `Start` in both directions.
+    pub fn set_align(&mut self, horizontal: utils::Align, vertical: utils::Align) {
+        self.h_align = horizontal;
+        self.v_align = vertical;
+        self.ask_for_repaint();
+    }
+
+    /// Overrides the minimal width of the label. If the text does not
+    /// fit, it is ellipsized.
+    pub fn set_min_width(&mut self, min_width: Option<f64>) {
+        self.min_width = min_width;
+    }
+
+    pub fn set_expandable(&mut self, width: bool, height: bool) {
+        self.width_expandable = width;
+        self.height_expandable = height;
+    }
+
+    fn make_layout(&self, cr: &cairo::Context) -> pango::Layout {
+        let lyt = utils::pango_layout(&self.text, cr);
+        if let Some(font) = &self.font {
+            lyt.set_font_description(Some(&pango::FontDescription::from_string(font)));
+        }
+        if self.markup {
+            lyt.set_markup(&self.text);
+        }
+        lyt
+    }
+
+    fn measure(&self) -> Size {
+        let sf = cairo::ImageSurface::create (cairo::Format::ARgb32, 8, 8).unwrap();
+        let cr = cairo::Context::new (&sf);
+
+        let (w, h) = self.make_layout(&cr).get_pixel_size();
+        Size { w: w.into(), h: h.into() }
+    }
+
+    fn remeasure(&mut self) {
+        let new_size = self.measure();
+        if new_size.w > self.min_size.w || new_size.h > self.min_size.h {
+            self.needs_relayout = true;
+        }
+        self.min_size = new_size;
+        self.ask_for_repaint();
     }
 }
 
@@ -35,17 +163,24 @@ impl Widget for Label {
     fn exposed(&mut self, _exposed: &ExposeArea, cr: &cairo::Context) {
 
         let pos = self.pos();
+        let size = self.size();
 
         cr.save();
 
-        cr.set_source_rgb(1., 1., 1.);
-        cr.translate(pos.x, pos.y);
-        pangocairo::functions::show_layout(&cr, &utils::pango_layout(&self.text, cr));
+        let (r, g, b) = self.color;
+        cr.set_source_rgb(r, g, b);
+        let lyt = self.make_layout(cr);
+        utils::show_layout_aligned(cr, &lyt, pos, size, self.h_align, self.v_align);
 
         cr.restore();
     }
 
-    fn min_size(&self) -> Size { self.min_size }
+    fn min_size(&self) -> Size {
+        Size { w: self.min_width.unwrap_or(self.min_size.w), h: self.min_size.h }
+    }
+
+    fn width_expandable(&self) -> bool { self.width_expandable }
+    fn height_expandable(&self) -> bool { self.height_expandable }
 }
 
 #[cfg(all(test, feature="testing"))]
@@ -58,4 +193,76 @@ mod tests {
 
         assert_eq!(label.text, "test label");
     }
+
+    #[test]
+    fn label_min_width_and_expandable() {
+        let mut label = Label::new("test label");
+        let natural = label.min_size();
+
+        label.set_min_width(Some(10.0));
+        assert_eq!(label.min_size(), Size { w: 10.0, h: natural.h });
+
+        label.set_expandable(false, true);
+        assert!(!label.width_expandable());
+        assert!(label.height_expandable());
+    }
+
+    #[test]
+    fn label_set_text() {
+        let mut label = Label::new("load");
+        let small = label.min_size();
+
+        label.set_text("loading sample file");
+        assert_eq!(label.text(), "loading sample file");
+        assert!(label.min_size().w > small.w);
+        assert!(label.needs_relayout());
+        assert!(!label.needs_relayout());
+
+        label.set_text("load");
+        assert_eq!(label.min_size(), small);
+        assert!(!label.needs_relayout());
+    }
+
+    #[test]
+    fn label_font() {
+        let mut label = Label::new("test label");
+        let small = label.min_size();
+
+        label.set_font(Some("Sans Bold 24px"));
+        assert_eq!(label.font(), Some("Sans Bold 24px"));
+        assert!(label.min_size().h > small.h);
+        assert!(label.needs_relayout());
+
+        let tester = crate::tests::SVGCairoTester::new(16., 16.);
+        let lyt = label.make_layout(tester.context());
+        let font = lyt.get_font_description().unwrap();
+        assert_eq!(font.get_weight(), pango::Weight::Bold);
+        assert_eq!(font.get_family().unwrap(), "Sans");
+
+        label.set_font(None);
+        assert_eq!(label.min_size(), small);
+    }
+
+    #[test]
+    fn label_color() {
+        let mut label = Label::new("test label");
+        assert_eq!(label.color(), (1., 1., 1.));
+        label.set_color((1., 0.5, 0.));
+        assert_eq!(label.color(), (1., 0.5, 0.));
+    }
+
+    #[test]
+    fn label_markup() {
+        let plain = Label::new("<b>Hz</b>");
+        let mut label = Label::new_markup("<b>Hz</b>");
+        assert!(label.is_markup());
+        assert!(!label.needs_relayout());
+        assert!(label.min_size().w < plain.min_size().w);
+
+        let tester = crate::tests::SVGCairoTester::new(16., 16.);
+        assert_eq!(label.make_layout(tester.context()).get_text().unwrap(), "Hz");
+
+        label.set_markup(false);
+        assert_eq!(label.min_size(), plain.min_size());
+    }
 }
@@ -9,7 +9,8 @@ pub struct LED {
     diameter: f64,
     hue: f64,
 
-    on: bool
+    on: bool,
+    enabled: bool
 }
 
 fn sanitize_hue(hue: f64) -> f64 {
@@ -25,7 +26,8 @@ impl LED {
         LED {
             hue: sanitize_hue(hue),
             diameter: style::LED_DIAMETER,
-            on: false
+            on: false,
+            enabled: true
         }
     }
 
@@ -41,6 +43,12 @@ impl LED {
         self
     }
 
+    /// A disabled LED is drawn desaturated but still shows its on state.
+    pub fn set_enabled(&mut self, enabled: bool) -> &mut LED {
+        self.enabled = enabled;
+        self
+    }
+
     pub fn render(&self, cr: &cairo::Context, pos: Coord) {
         cr.save();
         cr.translate(pos.x, pos.y);
@@ -52,7 +60,8 @@ impl LED {
         } else {
             0.5
         };
-        let (r, g, b) = utils::hsv_to_rgb(self.hue, 1., v);
+        let s = if self.enabled { 1.0 } else { style::WIDGET_COLOR_SAT_DISABLED };
+        let (r, g, b) = utils::hsv_to_rgb(self.hue, s, v);
 
         cr.set_source_rgb(r, g, b);
         cr.fill_preserve();
@@ -74,6 +83,7 @@ mod tests {
     fn led_create() {
         let led = LED::new(0.0);
         assert!(!led.on);
+        assert!(led.enabled);
         assert_eq!(led.hue, 0.0);
     }
 
@@ -174,4 +184,15 @@ mod tests {
 
         assert!(tester.contents().contains("<path style=\"fill-rule:nonzero;fill:rgb(0%,0%,50%);"));
     }
+
+    #[test]
+    fn led_draw_red_on_disabled() {
+        let mut led = LED::new(0.0);
+        led.set_on(true).set_enabled(false);
+
+        let tester = SVGCairoTester::new(16., 16.);
+        led.render(tester.context(), Coord { x: 8., y: 8. });
+
+        assert!(tester.contents().contains("<path style=\"fill-rule:nonzero;fill:rgb(100%,75%,75%);"));
+    }
 }
@@ -16,12 +16,20 @@ pub mod button;
 pub mod dial;
 pub mod osci;
 pub mod meter;
+pub mod enable;
+pub mod tween;
+pub mod radio;
+pub mod icon;
 
 pub use label::Label;
 pub use button::Button;
 pub use dial::Dial;
 pub use osci::Osci;
 pub use meter::Meter;
+pub use enable::Enable;
+pub use radio::RadioGroup;
+pub use icon::{Icon, IconError};
+pub use utils::Align;
 
 mod style;
 mod led;
@@ -178,7 +186,7 @@ mod tests {
 
         let dial1 = ui.new_widget( cascade! {
             dial::Dial::<dial::LinearScale>::new(0., 180., 10);
-            ..set_plate_draw( &|d: &dial::Dial<dial::LinearScale>, cr: &cairo::Context| { dial::draw_angle_tics(d, cr, 11) });
+            ..set_plate_draw(Box::new(|d: &dial::Dial<dial::LinearScale>, cr: &cairo::Context| { dial::draw_angle_tics(d, cr, 11) }));
             ..set_hue(Some(0.1));
             ..set_default_value(90.0);
             ..set_value(90.0);
@@ -186,15 +194,19 @@ mod tests {
 
         let dial2 = ui.new_widget( cascade! {
             dial::Dial::<dial::LogScale>::new(0.1, 1000., 10);
-            ..set_plate_draw( &|d: &dial::Dial<dial::LogScale>, cr: &cairo::Context| { dial::draw_angle_tics(d, cr, 5) });
+            ..set_plate_draw(Box::new(|d: &dial::Dial<dial::LogScale>, cr: &cairo::Context| { dial::draw_scale_tics(d, cr, true) }));
+            ..set_plate_margin(10.0);
             ..set_hue(Some(0.7));
             ..set_value(6.0);
         });
 
+        let unit = String::from("dB");
         let dial3 = ui.new_widget( cascade! {
             dial::Dial::<dial::LinearScale>::new(-72.0, 24.0, 32);
-            ..set_plate_draw( &|d: &dial::Dial<dial::LinearScale>, cr: &cairo::Context| { dial::draw_angle_tics(d, cr, 5) });
+            ..set_formater(Box::new(move |v: f64| format!("{:.1} {}", v, unit)));
+            ..set_plate_draw(Box::new(|d: &dial::Dial<dial::LinearScale>, cr: &cairo::Context| { dial::draw_angle_tics(d, cr, 5) }));
             ..set_hue(Some(0.4));
+            ..set_arc_origin(Some(0.0));
             ..set_value(-72.0);
         });
 
@@ -305,6 +317,16 @@ mod tests {
                 ui.widget(meter).set_level(v as f32);
             }
 
+            if ui.widget(dial1).wants_focus() {
+                ui.focus_widget(dial1);
+            }
+            if ui.widget(dial2).wants_focus() {
+                ui.focus_widget(dial2);
+            }
+            if ui.widget(dial3).wants_focus() {
+                ui.focus_widget(dial3);
+            }
+
             let w = ui.widget(toggle_button);
             if let Some(ts) = w.changed_toggle_state() {
                 w.set_toggle_state(ts);
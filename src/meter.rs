@@ -1,35 +1,44 @@
 use pugl_ui::widget::*;
 
+use crate::tween::{self, Tween, Easing};
+
 const MIN_LEVEL: f32 = -40.0;
 const MAX_LEVEL: f32 = 12.0;
 const METER_STEP: f32 = 1.0;
 const MIN_WIDTH: f64 = 12.0;
 
 const LEVEL_RETAIN_TIME: f64 = 3.0;
+const PEAK_FALLOFF_TIME: f64 = 1.5;
 
 
 pub struct Meter {
     stub: WidgetStub,
     current_level: f32,
 
-    retained_level: Option<(usize, f32)>,
-    num_retains: usize,
+    retained_level: Option<f32>,
+    peak_falloff: Option<Tween>,
 }
 
 impl Meter {
-    pub fn new(period: f64) -> Box<Meter> {
+    /// Creates a meter.
+    ///
+    /// `period` is ignored as the peak hold and falloff are timer
+    /// driven. It is only kept for compatibility.
+    pub fn new(_period: f64) -> Box<Meter> {
         Box::new(Meter {
             stub: WidgetStub::default(),
             current_level: -160.0,
             retained_level: None,
-            num_retains: (LEVEL_RETAIN_TIME/period).round() as usize
+            peak_falloff: None,
         })
     }
 
     pub fn set_level(&mut self, level: f32) {
         let new_level = level.max(MIN_LEVEL).min(MAX_LEVEL);
-        if self.retained_level.map_or(true, |(_, l)| new_level > l) {
-            self.retained_level = Some((self.num_retains, new_level));
+        if self.retained_level.map_or(true, |l| new_level > l) {
+            self.retained_level = Some(new_level);
+            self.peak_falloff = None;
+            self.request_reminder(LEVEL_RETAIN_TIME);
         }
         if (new_level - self.current_level).abs() > f32::EPSILON * self.current_level ||
             self.retained_level.is_some() {
@@ -41,6 +50,23 @@ impl Meter {
     pub fn level(&self) -> f32 {
         self.current_level
     }
+
+    pub fn retained_level(&self) -> Option<f32> {
+        self.retained_level
+    }
+
+    /// Called when the retain time has passed. Lets the retained
+    /// level fall down at a constant rate.
+    fn start_peak_falloff(&mut self) -> bool {
+        let level = match self.retained_level {
+            Some(level) => level,
+            None => return false
+        };
+        let duration = PEAK_FALLOFF_TIME * f64::from((level - MIN_LEVEL) / (MAX_LEVEL - MIN_LEVEL));
+        self.peak_falloff = Some(Tween::new(level.into(), MIN_LEVEL.into(), duration, Easing::Linear));
+        self.request_reminder(tween::FRAME_PERIOD);
+        true
+    }
 }
 
 impl Widget for Meter {
@@ -136,29 +162,20 @@ impl Widget for Meter {
             cr.fill();
         }
 
-        self.retained_level = match self.retained_level {
-            Some((num, level)) => {
-                let rgb = match level {
-                    l if l < -18.0 => (0., 0.5, 0.),
-                    l if l < -9.0 => (0., 1., 0.),
-                    l if l < -3.0 => (1., 1., 0.),
-                    l if l < 0.0 => (1., 0.5, 0.),
-                    _ => (1.0, 0.0, 0.0)
-                };
-
-                cr.set_source(&make_grad(left, top, right, rgb));
-                let y = scale_dB(height, level);
-                cr.rectangle(left, bottom - y, width, y - scale_dB(height, level - METER_STEP));
-                cr.fill();
-
-                if num > 1 {
-                    Some((num-1, level))
-                } else {
-                    None
-                }
-            }
-            None => None
-        };
+        if let Some(level) = self.retained_level {
+            let rgb = match level {
+                l if l < -18.0 => (0., 0.5, 0.),
+                l if l < -9.0 => (0., 1., 0.),
+                l if l < -3.0 => (1., 1., 0.),
+                l if l < 0.0 => (1., 0.5, 0.),
+                _ => (1.0, 0.0, 0.0)
+            };
+
+            cr.set_source(&make_grad(left, top, right, rgb));
+            let y = scale_dB(height, level);
+            cr.rectangle(left, bottom - y, width, y - scale_dB(height, level - METER_STEP));
+            cr.fill();
+        }
 
         cr.set_source_rgb(0., 0., 0.);
         cr.set_line_width(1.0);
@@ -179,6 +196,23 @@ impl Widget for Meter {
     fn height_expandable(&self) -> bool {
         true
     }
+
+    fn reminder_handler(&mut self) -> bool {
+        let (running, level) = match self.peak_falloff.as_mut() {
+            Some(tween) => (tween.advance(tween::FRAME_PERIOD), tween.value() as f32),
+            None => return self.start_peak_falloff()
+        };
+
+        self.ask_for_repaint();
+        if running && level > self.current_level {
+            self.retained_level = Some(level);
+            true
+        } else {
+            self.retained_level = None;
+            self.peak_falloff = None;
+            false
+        }
+    }
 }
 
 #[allow(non_snake_case)]
@@ -200,3 +234,49 @@ fn make_grad(left: f64, top: f64, right: f64,
 
     grad
 }
+
+#[cfg(all(test, feature="testing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peak_hold_and_falloff() {
+        let mut meter = Meter::new(0.1);
+        meter.set_level(0.0);
+        assert_eq!(meter.retained_level(), Some(0.0));
+        assert_eq!(meter.reminder_request(), Some(LEVEL_RETAIN_TIME));
+
+        meter.set_level(-30.0);
+        assert_eq!(meter.reminder_request(), None);
+        assert_eq!(meter.retained_level(), Some(0.0));
+
+        assert!(meter.reminder_handler());
+        assert_eq!(meter.reminder_request(), Some(tween::FRAME_PERIOD));
+        assert_eq!(meter.retained_level(), Some(0.0));
+
+        let mut last = 0.0;
+        while meter.reminder_handler() {
+            let level = meter.retained_level().unwrap();
+            assert!(level < last);
+            last = level;
+        }
+        assert!(last > -30.0);
+        assert_eq!(meter.retained_level(), None);
+    }
+
+    #[test]
+    fn new_peak_restarts_hold() {
+        let mut meter = Meter::new(0.1);
+        meter.set_level(-20.0);
+        meter.set_level(-35.0);
+        meter.reminder_request();
+        meter.reminder_handler();
+        meter.reminder_handler();
+        let falling = meter.retained_level().unwrap();
+        assert!(falling < -20.0);
+
+        meter.set_level(-10.0);
+        assert_eq!(meter.retained_level(), Some(-10.0));
+        assert_eq!(meter.reminder_request(), Some(LEVEL_RETAIN_TIME));
+    }
+}
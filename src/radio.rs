@@ -0,0 +1,175 @@
+use pugl_ui::ui::UI;
+use pugl_ui::widget::*;
+
+use crate::button::Button;
+
+/// A group of toggle buttons of which exactly one is on
+///
+/// The group itself is not a widget. It creates the buttons in the
+/// `UI` and keeps their handles, so that the application can pack
+/// them into a layout using `buttons()`.
+///
+/// After each event cycle the application polls `changed_index()`
+/// which switches the buttons accordingly.
+pub struct RadioGroup {
+    buttons: Vec<WidgetHandle<Button>>,
+    index: usize,
+}
+
+impl RadioGroup {
+    /// Creates one toggle button for each label. The first one is on.
+    ///
+    /// Panics if `labels` is empty.
+    pub fn new<RW: Widget + 'static>(ui: &mut UI<RW>, labels: &[&str], led_hue: f64) -> RadioGroup {
+        if labels.is_empty() {
+            panic!("RadioGroup needs at least one button");
+        }
+        let buttons = labels.iter()
+            .map(|label| ui.new_widget(Button::new_toggle_button(label, led_hue)))
+            .collect();
+        let mut group = RadioGroup { buttons, index: 0 };
+        group.apply_index(ui);
+        group
+    }
+
+    pub fn buttons(&self) -> &[WidgetHandle<Button>] {
+        &self.buttons
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Selects the button `index`, e.g. when the host sends a new
+    /// parameter value. Indices out of range are ignored.
+    pub fn set_index<RW: Widget + 'static>(&mut self, ui: &mut UI<RW>, index: usize) {
+        if index < self.buttons.len() {
+            self.index = index;
+            self.apply_index(ui);
+        }
+    }
+
+    /// Returns the new index if the user has selected another button.
+    ///
+    /// Clicking on the button that is already on does not turn it off.
+    pub fn changed_index<RW: Widget + 'static>(&mut self, ui: &mut UI<RW>) -> Option<usize> {
+        let mut changed = None;
+        for (i, btn) in self.buttons.iter().enumerate() {
+            if ui.widget(*btn).changed_toggle_state() == Some(true) && i != self.index {
+                changed = Some(i);
+            }
+        }
+        if let Some(index) = changed {
+            self.index = index;
+        }
+        self.apply_index(ui);
+        changed
+    }
+
+    fn apply_index<RW: Widget + 'static>(&self, ui: &mut UI<RW>) {
+        for (i, btn) in self.buttons.iter().enumerate() {
+            let w = ui.widget(*btn);
+            let on = i == self.index;
+            if w.toggle_state() != Some(on) {
+                w.set_toggle_state(on);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature="testing"))]
+mod tests {
+    use super::*;
+    use pugl_sys::*;
+    use pugl_ui::layout::stacklayout::*;
+
+    #[derive(Default)]
+    struct RootWidget {
+        stub: WidgetStub
+    }
+
+    impl Widget for RootWidget {
+        widget_stub!();
+    }
+
+    fn toggle_states(ui: &mut UI<RootWidget>, group: &RadioGroup) -> Vec<Option<bool>> {
+        group.buttons().iter().map(|b| ui.widget(*b).toggle_state()).collect()
+    }
+
+    fn click(view: &mut PuglView<UI<RootWidget>>, button: WidgetHandle<Button>) {
+        let pos = {
+            let w = view.handle().widget(button);
+            let (pos, size) = (w.pos(), w.size());
+            Coord { x: pos.x + size.w/2., y: pos.y + size.h/2. }
+        };
+        for data in &[EventType::MouseButtonPress(MouseButton { num: 1, modifiers: Modifiers::default() }),
+                      EventType::MouseButtonRelease(MouseButton { num: 1, modifiers: Modifiers::default() })] {
+            view.queue_event(Event { data: *data, context: EventContext { pos, ..Default::default() } });
+            view.handle().update(-1.0);
+        }
+    }
+
+    fn radio_view() -> (Box<PuglView<UI<RootWidget>>>, RadioGroup) {
+        let rw = Box::new(RootWidget::default());
+        let mut view = PuglView::new(std::ptr::null_mut(), |pv| UI::new_scaled(pv, rw, 1.0));
+
+        let ui = view.handle();
+        let group = RadioGroup::new(ui, &["sine", "square", "saw"], 0.3);
+        for btn in group.buttons() {
+            ui.pack_to_layout(*btn, ui.root_layout(), StackDirection::Back);
+        }
+        ui.do_layout();
+        ui.show_window();
+
+        (view, group)
+    }
+
+    #[test]
+    fn radio_group_initial_state() {
+        let (mut view, group) = radio_view();
+        assert_eq!(group.index(), 0);
+        assert_eq!(toggle_states(view.handle(), &group), vec![Some(true), Some(false), Some(false)]);
+    }
+
+    #[test]
+    fn radio_group_click() {
+        let (mut view, mut group) = radio_view();
+        click(&mut view, group.buttons()[2]);
+
+        let ui = view.handle();
+        assert_eq!(group.changed_index(ui), Some(2));
+        assert_eq!(group.index(), 2);
+        assert_eq!(toggle_states(ui, &group), vec![Some(false), Some(false), Some(true)]);
+        assert_eq!(group.changed_index(ui), None);
+    }
+
+    #[test]
+    fn radio_group_click_selected_stays_on() {
+        let (mut view, mut group) = radio_view();
+        click(&mut view, group.buttons()[0]);
+
+        let ui = view.handle();
+        assert_eq!(group.changed_index(ui), None);
+        assert_eq!(toggle_states(ui, &group), vec![Some(true), Some(false), Some(false)]);
+    }
+
+    #[test]
+    fn radio_group_set_index() {
+        let (mut view, mut group) = radio_view();
+        let ui = view.handle();
+        group.set_index(ui, 1);
+        assert_eq!(group.index(), 1);
+        assert_eq!(toggle_states(ui, &group), vec![Some(false), Some(true), Some(false)]);
+        assert_eq!(group.changed_index(ui), None);
+
+        group.set_index(ui, 3);
+        assert_eq!(group.index(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn radio_group_empty() {
+        let (mut view, _) = radio_view();
+        RadioGroup::new(view.handle(), &[], 0.0);
+    }
+}
@@ -5,8 +5,14 @@ pub const WIDGET_COLOR_SAT: f64 = 1.0;
 pub const BRIGHTNESS_NORMAL: f64 = 0.6;
 pub const BRIGHTNESS_HOVER: f64 = 0.8;
 
+pub const WIDGET_COLOR_SAT_DISABLED: f64 = 0.25;
+pub const BRIGHTNESS_DISABLED: f64 = 0.35;
+
 pub const BRIGHTNESS_BUTTON_NORMAL: f64 = 0.3;
 pub const BRIGHTNESS_BUTTON_HOVER: f64 = 0.4;
+pub const BRIGHTNESS_BUTTON_DISABLED: f64 = 0.2;
+
+pub const BRIGHTNESS_TEXT_DISABLED: f64 = 0.5;
 
 
 pub const DIAL_DIAMETER: f64 = 36.0;
@@ -0,0 +1,146 @@
+//! Timer driven animations
+//!
+//! A [`Tween`](struct.Tween.html) interpolates a value from a start
+//! to a target within a given duration. It does not know about time
+//! by itself but is advanced by the widget, usually from within
+//! `Widget::reminder_handler()` after having requested a reminder
+//! with a period of [`FRAME_PERIOD`](constant.FRAME_PERIOD.html).
+
+/// The period of an animation frame in seconds
+pub const FRAME_PERIOD: f64 = 1.0 / 60.0;
+
+/// The easing curve of a `Tween`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseOut,
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps the linear progress `t` in `0.0..=1.0` to the eased progress
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.max(0.0).min(1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Tween {
+    start: f64,
+    target: f64,
+    duration: f64,
+    elapsed: f64,
+    easing: Easing,
+}
+
+impl Tween {
+    pub fn new(start: f64, target: f64, duration: f64, easing: Easing) -> Tween {
+        Tween { start, target, duration, elapsed: 0.0, easing }
+    }
+
+    /// A tween that has already arrived at `value`
+    pub fn constant(value: f64) -> Tween {
+        Tween::new(value, value, 0.0, Easing::Linear)
+    }
+
+    /// Starts over from the current value towards `target`
+    pub fn retarget(&mut self, target: f64, duration: f64) {
+        *self = Tween::new(self.value(), target, duration, self.easing);
+    }
+
+    /// Advances the tween by `dt` seconds.
+    ///
+    /// Returns `true` if the tween is still running afterwards.
+    pub fn advance(&mut self, dt: f64) -> bool {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        self.is_running()
+    }
+
+    /// Jumps to the target
+    pub fn finish(&mut self) {
+        self.elapsed = self.duration;
+    }
+
+    pub fn value(&self) -> f64 {
+        if !self.is_running() {
+            return self.target;
+        }
+        self.start + (self.target - self.start) * self.easing.apply(self.elapsed / self.duration)
+    }
+
+    pub fn target(&self) -> f64 {
+        self.target
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.elapsed < self.duration
+    }
+}
+
+#[cfg(all(test, feature="testing"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easing_end_points() {
+        for easing in &[Easing::Linear, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!(easing.apply(0.0), 0.0);
+            assert_eq!(easing.apply(1.0), 1.0);
+            assert_eq!(easing.apply(-1.0), 0.0);
+            assert_eq!(easing.apply(2.0), 1.0);
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseOut.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn tween_advance() {
+        let mut tween = Tween::new(1.0, 3.0, 1.0, Easing::Linear);
+        assert!(tween.is_running());
+        assert_eq!(tween.value(), 1.0);
+        assert!(tween.advance(0.25));
+        assert_eq!(tween.value(), 1.5);
+        assert!(!tween.advance(1.0));
+        assert_eq!(tween.value(), 3.0);
+        assert!(!tween.advance(1.0));
+        assert_eq!(tween.value(), 3.0);
+    }
+
+    #[test]
+    fn tween_constant() {
+        let tween = Tween::constant(2.0);
+        assert!(!tween.is_running());
+        assert_eq!(tween.value(), 2.0);
+        assert_eq!(tween.target(), 2.0);
+    }
+
+    #[test]
+    fn tween_retarget() {
+        let mut tween = Tween::new(0.0, 4.0, 1.0, Easing::Linear);
+        tween.advance(0.5);
+        tween.retarget(0.0, 2.0);
+        assert_eq!(tween.value(), 2.0);
+        assert_eq!(tween.target(), 0.0);
+        tween.advance(1.0);
+        assert_eq!(tween.value(), 1.0);
+    }
+
+    #[test]
+    fn tween_finish() {
+        let mut tween = Tween::new(0.0, 4.0, 1.0, Easing::EaseOut);
+        tween.finish();
+        assert!(!tween.is_running());
+        assert_eq!(tween.value(), 4.0);
+    }
+}
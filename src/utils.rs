@@ -7,6 +7,26 @@ use crate::style;
 
 pub type RGB = (f64, f64, f64);
 
+/// Alignment of a widget's content within the space allocated by the layout
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Align {
+    Start,
+    Center,
+    End
+}
+
+impl Align {
+    /// The offset of content of size `extent` in `available` space
+    pub fn offset(self, available: f64, extent: f64) -> f64 {
+        let space = (available - extent).max(0.0);
+        match self {
+            Align::Start => 0.0,
+            Align::Center => space / 2.,
+            Align::End => space
+        }
+    }
+}
+
 pub fn rounded_rectangle(cr: &cairo::Context, pos: Coord, size: Size, radius: f64) {
     const DEG: f64 = PI / 180.0;
 
@@ -43,16 +63,24 @@ pub fn inactive_gradient(pos: Coord, size: Size, rgb: (f64, f64, f64)) -> cairo:
 }
 
 pub fn widget_rgb(hovered: bool, hue: Option<f64>) -> RGB {
-    let v = if hovered {
-        style::BRIGHTNESS_HOVER
-    } else {
-        style::BRIGHTNESS_NORMAL
-    };
+    widget_rgb_hover_fraction(if hovered { 1.0 } else { 0.0 }, hue)
+}
+
+/// The widget color blended between normal and hovered brightness,
+/// e.g. for a hover transition animated by a `tween::Tween`.
+pub fn widget_rgb_hover_fraction(hover: f64, hue: Option<f64>) -> RGB {
+    let hover = hover.max(0.0).min(1.0);
+    let v = style::BRIGHTNESS_NORMAL + hover * (style::BRIGHTNESS_HOVER - style::BRIGHTNESS_NORMAL);
 
     let (h, s) = hue.map_or((1.0, 0.0), |h| (h, style::WIDGET_COLOR_SAT));
     hsv_to_rgb(h, s, v)
 }
 
+pub fn widget_rgb_disabled(hue: Option<f64>) -> RGB {
+    let (h, s) = hue.map_or((1.0, 0.0), |h| (h, style::WIDGET_COLOR_SAT_DISABLED));
+    hsv_to_rgb(h, s, style::BRIGHTNESS_DISABLED)
+}
+
 pub fn hsv_to_rgb(h: f64, s: f64, v: f64) -> RGB {
     let c = v * s;
 
@@ -87,6 +115,24 @@ pub fn pango_layout(text: &str, cr: &cairo::Context) -> pango::Layout {
     lyt
 }
 
+/// Shows `lyt` aligned in the rectangle at `pos` of `size`.
+///
+/// Text which is too wide is ellipsized at the end.
+pub fn show_layout_aligned(cr: &cairo::Context, lyt: &pango::Layout,
+                           pos: Coord, size: Size, h_align: Align, v_align: Align) {
+    let (w, _) = lyt.get_pixel_size();
+    if f64::from(w) > size.w {
+        lyt.set_width((size.w.max(0.0) * f64::from(pango::SCALE)) as i32);
+        lyt.set_ellipsize(pango::EllipsizeMode::End);
+    }
+
+    let (w, h) = lyt.get_pixel_size();
+    cr.save();
+    cr.translate(pos.x + h_align.offset(size.w, w.into()), pos.y + v_align.offset(size.h, h.into()));
+    pangocairo::functions::show_layout(cr, lyt);
+    cr.restore();
+}
+
 
 #[cfg(all(test, feature="testing"))]
 mod tests {
@@ -201,6 +247,48 @@ mod tests {
         assert_eq!(b, 1.0);
     }
 
+    #[test]
+    fn widget_rgb_hover_fraction_blends() {
+        assert_eq!(widget_rgb_hover_fraction(0.0, Some(0.3)), widget_rgb(false, Some(0.3)));
+        assert_eq!(widget_rgb_hover_fraction(1.0, Some(0.3)), widget_rgb(true, Some(0.3)));
+        let (r, _, _) = widget_rgb_hover_fraction(0.5, None);
+        assert!((r - (style::BRIGHTNESS_NORMAL + style::BRIGHTNESS_HOVER) / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn widget_rgb_disabled_desaturated() {
+        let (r, g, b) = widget_rgb_disabled(Some(0.0));
+        assert_eq!(r, style::BRIGHTNESS_DISABLED);
+        assert!(g < r && g > 0.0);
+        assert_eq!(g, b);
+
+        let (r, g, b) = widget_rgb_disabled(None);
+        assert_eq!(r, style::BRIGHTNESS_DISABLED);
+        assert_eq!(g, r);
+        assert_eq!(b, r);
+    }
+
+    #[test]
+    fn align_offset() {
+        assert_eq!(Align::Start.offset(10.0, 4.0), 0.0);
+        assert_eq!(Align::Center.offset(10.0, 4.0), 3.0);
+        assert_eq!(Align::End.offset(10.0, 4.0), 6.0);
+        assert_eq!(Align::End.offset(4.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn show_layout_aligned_ellipsizes() {
+        let tester = SVGCairoTester::new(64., 16.);
+        let lyt = pango_layout("a text which is too long", tester.context());
+        show_layout_aligned(tester.context(), &lyt, Coord::default(), Size { w: 40., h: 16. }, Align::Start, Align::Start);
+        assert!(lyt.is_ellipsized());
+        assert!(lyt.get_pixel_size().0 <= 40);
+
+        let lyt = pango_layout("fits", tester.context());
+        show_layout_aligned(tester.context(), &lyt, Coord::default(), Size { w: 64., h: 16. }, Align::End, Align::Center);
+        assert!(!lyt.is_ellipsized());
+    }
+
     #[test]
     fn pango_layout_text() {
         let tester = SVGCairoTester::new(16., 16.);